@@ -0,0 +1,76 @@
+pub mod render;
+pub mod selfplay;
+pub mod url;
+
+use crate::tetsimu2::core::Tetromino;
+use std::convert::TryFrom;
+
+pub fn execute(command: &str, args: &[String]) -> Result<(), String> {
+  match command {
+    "render" => render::execute(args),
+    "selfplay" => selfplay::execute(args),
+    "url" => url::execute(args),
+    _ => Err(format!("Unknown command '{}'.", command)),
+  }
+}
+
+pub fn parse_options(args: &[String]) -> Result<Vec<(String, String)>, String> {
+  let mut options = vec![];
+  let mut args = args.iter();
+  while let Some(name) = args.next() {
+    if !name.starts_with("--") {
+      return Err(format!("Unexpected argument '{}'.", name));
+    }
+
+    let value = args
+      .next()
+      .ok_or(format!("Option '{}' requires a value.", name))?;
+    options.push((String::from(&name[2..]), value.clone()));
+  }
+
+  Ok(options)
+}
+
+pub fn parse_tetrominos(value: &str) -> Result<Vec<Tetromino>, String> {
+  if value.is_empty() {
+    return Err(String::from("Tetrominos must not be empty."));
+  }
+
+  value.chars().map(Tetromino::try_from).collect()
+}
+
+pub fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+  value
+    .parse()
+    .map_err(|_| format!("'--{}' must be a number. ({})", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_options_test() {
+    let args: Vec<String> = vec!["--out", "a.svg", "--tetfu", "v115@vhAAAA"]
+      .into_iter()
+      .map(String::from)
+      .collect();
+
+    assert_eq!(
+      parse_options(&args),
+      Ok(vec![
+        (String::from("out"), String::from("a.svg")),
+        (String::from("tetfu"), String::from("v115@vhAAAA")),
+      ])
+    );
+
+    assert_eq!(
+      parse_options(&args[0..1]),
+      Err(String::from("Option '--out' requires a value."))
+    );
+    assert_eq!(
+      parse_options(&args[1..]),
+      Err(String::from("Unexpected argument 'a.svg'."))
+    );
+  }
+}
@@ -0,0 +1,100 @@
+use crate::commands::parse_number;
+use crate::commands::parse_options;
+use crate::commands::parse_tetrominos;
+use crate::renderer::core::RenderContent;
+use crate::renderer::core::RenderOptions;
+use crate::renderer::gif_renderer::GifRenderer;
+use crate::renderer::skin::Skin;
+use crate::renderer::svg_renderer::SvgRenderer;
+use crate::tetfu::tetfu_decoder::TetfuDecoder;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::url::Tetsimu2UrlDecoder;
+use std::fs::File;
+use std::io::Write;
+
+const DEFAULT_FRAME_DELAY: u16 = 100;
+
+pub fn execute(args: &[String]) -> Result<(), String> {
+  let mut render_options = RenderOptions::default();
+  let mut contents = vec![];
+  let mut hold = None;
+  let mut nexts = None;
+  let mut out = None;
+  let mut frame_delay = DEFAULT_FRAME_DELAY;
+
+  for (name, value) in parse_options(args)? {
+    match name.as_str() {
+      "tetfu" => {
+        let decoder = TetfuDecoder::new();
+        let content = decoder.decode(value)?;
+        contents.push(RenderContent::new(content.field));
+      }
+      "url" => {
+        let decoder = Tetsimu2UrlDecoder::new();
+        let content = decoder.decode(&value)?;
+        let mut render_content = RenderContent::new(content.field);
+        render_content.hold = content.hold.holded;
+        render_content.nexts = content.nexts;
+        contents.push(render_content);
+      }
+      "hold" => hold = Some(parse_tetrominos(&value)?.remove(0)),
+      "nexts" => nexts = Some(parse_tetrominos(&value)?),
+      "out" => out = Some(value),
+      "cell-size" => render_options.cell_size = parse_number(&name, &value)?,
+      "height" => render_options.field_height = parse_number(&name, &value)?,
+      "skin" => {
+        render_options.skin = Skin::from_name(&value).ok_or(format!("Unknown skin '{}'.", value))?
+      }
+      "delay" => frame_delay = parse_number(&name, &value)?,
+      _ => return Err(format!("Unknown option '--{}'.", name)),
+    }
+  }
+
+  if contents.is_empty() {
+    return Err(String::from(
+      "At least one '--tetfu' or '--url' is required.",
+    ));
+  }
+
+  if render_options.cell_size == 0 {
+    return Err(String::from("'--cell-size' must be greater than 0."));
+  }
+
+  if render_options.field_height <= 0 || render_options.field_height > MAX_FIELD_HEIGHT {
+    return Err(format!(
+      "'--height' must be between 1 and {}.",
+      MAX_FIELD_HEIGHT
+    ));
+  }
+
+  // '--hold' and '--nexts' override what each page got from its URL.
+  for content in contents.iter_mut() {
+    if hold.is_some() {
+      content.hold = hold;
+    }
+    if let Some(nexts) = &nexts {
+      content.nexts = nexts.clone();
+    }
+  }
+
+  let out = out.ok_or("'--out' is required.")?;
+  let data = if out.ends_with(".svg") {
+    if contents.len() > 1 {
+      return Err(String::from("SVG output supports only one page."));
+    }
+
+    SvgRenderer::new(render_options)
+      .render(&contents[0])
+      .into_bytes()
+  } else if out.ends_with(".gif") {
+    GifRenderer::new(render_options, frame_delay).render(&contents)?
+  } else {
+    return Err(format!("Unsupported output format '{}'.", out));
+  };
+
+  let mut file = File::create(&out).map_err(|e| format!("{:#}", e))?;
+  file.write_all(&data).map_err(|e| format!("{:#}", e))?;
+  println!("Rendered {} page(s) to {}", contents.len(), out);
+
+  Ok(())
+}
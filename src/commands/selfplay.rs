@@ -0,0 +1,140 @@
+use crate::commands::parse_number;
+use crate::commands::parse_options;
+use crate::hub::processors::tutor::bot_options::cold_clear_options;
+use crate::hub::processors::tutor::bot_options::evaluator;
+use crate::hub::processors::tutor::bot_options::think_budget;
+use crate::hub::processors::tutor::cold_clear_backend::ColdClearBackend;
+use crate::hub::processors::tutor::selfplay::play_game;
+use crate::hub::processors::tutor::selfplay::Bot;
+use crate::hub::processors::tutor::selfplay::Summary;
+use crate::hub::processors::tutor::tutor_backend::create_backend;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::settings::Settings;
+use crate::settings::TutorBackendKind;
+use crate::settings::TutorOptions;
+use std::fs::File;
+use std::io::Write;
+
+const DEFAULT_GAMES: u32 = 1;
+const DEFAULT_PIECES: usize = 100;
+
+pub fn execute(args: &[String]) -> Result<(), String> {
+  let mut games = DEFAULT_GAMES;
+  let mut seed = 0;
+  let mut max_pieces = DEFAULT_PIECES;
+  let mut settings = None;
+  let mut bots: Vec<TutorOptions> = vec![];
+  let mut replays = None;
+
+  for (name, value) in parse_options(args)? {
+    match name.as_str() {
+      "games" => games = parse_number(&name, &value)?,
+      "seed" => seed = parse_number(&name, &value)?,
+      "pieces" => max_pieces = parse_number(&name, &value)?,
+      "config" => settings = Some(Settings::read_file(&value)?),
+      "bot" => bots
+        .push(serde_json::from_str(&value).map_err(|e| format!("Invalid '--bot' options. {}", e))?),
+      "replays" => replays = Some(value),
+      _ => return Err(format!("Unknown option '--{}'.", name)),
+    }
+  }
+
+  if bots.is_empty() {
+    bots.push(TutorOptions::default());
+  }
+  if bots.len() > 2 {
+    return Err(String::from(
+      "At most two '--bot' can play against each other.",
+    ));
+  }
+
+  let base = settings
+    .as_ref()
+    .map(|x| x.tutor.clone())
+    .unwrap_or_default();
+  let bots: Vec<TutorOptions> = bots.iter().map(|x| base.merge(x)).collect();
+
+  let mut records = vec![];
+  let mut summaries = vec![Summary::default(); bots.len()];
+  for game in 0..games {
+    let game_seed = seed + game as u64;
+    let players = bots
+      .iter()
+      .map(|options| {
+        Ok(Bot {
+          backend: backend(settings.as_ref(), options)?,
+          budget: think_budget(options),
+        })
+      })
+      .collect::<Result<Vec<_>, String>>()?;
+
+    let record = play_game(players, game_seed, max_pieces);
+    println!(
+      "game {} (seed {}): {}",
+      game + 1,
+      game_seed,
+      record
+        .players
+        .iter()
+        .map(|x| format!("{:?} after {} pieces", x.end, x.moves.len()))
+        .collect::<Vec<_>>()
+        .join(" / ")
+    );
+    for (i, summary) in summaries.iter_mut().enumerate() {
+      summary.add(&record, i);
+    }
+    records.push(record);
+  }
+
+  for (i, (options, summary)) in bots.iter().zip(&summaries).enumerate() {
+    println!();
+    println!(
+      "bot {}: {}",
+      i + 1,
+      serde_json::to_string(options).map_err(|e| e.to_string())?
+    );
+    println!("  pieces: {}", summary.pieces);
+    println!("  attack per piece: {:.3}", summary.attack_per_piece());
+    println!("  pps: {:.2}", summary.pieces_per_second());
+    println!("  pc: {}", summary.pcs);
+    println!(
+      "  death rate: {:.1}% ({}/{})",
+      summary.death_rate() * 100.0,
+      summary.deaths,
+      summary.games
+    );
+    if summary.failures > 0 {
+      println!("  failures: {}", summary.failures);
+    }
+    if bots.len() > 1 {
+      println!("  wins: {}", summary.wins);
+    }
+  }
+
+  if let Some(path) = replays {
+    let json = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    let mut file = File::create(&path).map_err(|e| format!("{:#}", e))?;
+    file
+      .write_all(json.as_bytes())
+      .map_err(|e| format!("{:#}", e))?;
+  }
+
+  Ok(())
+}
+
+fn backend(
+  settings: Option<&Settings>,
+  options: &TutorOptions,
+) -> Result<Box<dyn TutorBackend>, String> {
+  let cc_options = cold_clear_options(options);
+  let evaluator = evaluator(options)?;
+  let kind = options.backend.unwrap_or(TutorBackendKind::ColdClear);
+
+  match (settings, kind) {
+    (Some(settings), _) => create_backend(settings, kind, &cc_options, &evaluator),
+    (None, TutorBackendKind::ColdClear) => {
+      Ok(Box::new(ColdClearBackend::new(&cc_options, &evaluator)))
+    }
+    (None, TutorBackendKind::Tbp) => Err(String::from("A TBP bot requires '--config'.")),
+  }
+}
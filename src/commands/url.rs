@@ -0,0 +1,59 @@
+use crate::commands::parse_options;
+use crate::commands::parse_tetrominos;
+use crate::tetfu::core::Tetsimu2Content;
+use crate::tetfu::tetfu_decoder::TetfuDecoder;
+use crate::tetfu::tetfu_encoder::TetfuEncoder;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::url::Tetsimu2UrlContent;
+use crate::tetsimu2::url::Tetsimu2UrlDecoder;
+use crate::tetsimu2::url::Tetsimu2UrlEncoder;
+use crate::tetsimu2::url::DEFAULT_BASE_URL;
+
+pub fn execute(args: &[String]) -> Result<(), String> {
+  let mut content = Tetsimu2UrlContent::new(Field::new());
+  let mut base_url = String::from(DEFAULT_BASE_URL);
+  let mut url_to_decode = None;
+
+  for (name, value) in parse_options(args)? {
+    match name.as_str() {
+      "tetfu" => {
+        let decoder = TetfuDecoder::new();
+        content.field = decoder.decode(value)?.field;
+      }
+      "nexts" => content.nexts = parse_tetrominos(&value)?,
+      "hold" => content.hold.holded = Some(parse_tetrominos(&value)?.remove(0)),
+      "base-url" => base_url = value,
+      "decode" => url_to_decode = Some(value),
+      _ => return Err(format!("Unknown option '--{}'.", name)),
+    }
+  }
+
+  if let Some(url) = url_to_decode {
+    let decoded = Tetsimu2UrlDecoder::new().decode(&url)?;
+    let tetfu = TetfuEncoder::new().encode(&Tetsimu2Content {
+      field: decoded.field,
+      comment: String::from(""),
+    });
+
+    println!("tetfu: {}", tetfu);
+    println!(
+      "nexts: {}",
+      decoded
+        .nexts
+        .iter()
+        .map(|t| format!("{:?}", t))
+        .collect::<String>()
+    );
+    println!(
+      "hold: {}",
+      decoded
+        .hold
+        .holded
+        .map_or(String::from(""), |t| format!("{:?}", t))
+    );
+  } else {
+    println!("{}", Tetsimu2UrlEncoder::new(&base_url).encode(&content));
+  }
+
+  Ok(())
+}
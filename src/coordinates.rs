@@ -0,0 +1,286 @@
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::XY;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+
+// Every system counts x from the left wall and y upwards from the bottom row. They differ in
+// which cell of a piece its position refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSystem {
+  // Origins of tetsimu2's own block tables.
+  Tetsimu2,
+  // Rotation centre, with the I and O pieces rotating around a cell of their own.
+  Libtetris,
+  // Same as libtetris, which it was specified from.
+  Tbp,
+  // Same as libtetris, with the y axis already flipped from fumen's top-down page data.
+  Fumen,
+  // Same as fumen, which sfinder reads and writes.
+  Sfinder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+  pub r#type: Tetromino,
+  pub direction: Direction,
+  pub x: i32,
+  pub y: i32,
+}
+
+impl Placement {
+  pub fn blocks(&self, system: CoordinateSystem) -> Vec<XY> {
+    match system {
+      CoordinateSystem::Tetsimu2 => self.to_current_tetromino().blocks(),
+      _ => rotation_centre_blocks(self),
+    }
+  }
+
+  // The placement in `system` occupying exactly `cells`.
+  pub fn from_blocks(
+    system: CoordinateSystem,
+    r#type: Tetromino,
+    direction: Direction,
+    cells: &[XY],
+  ) -> Option<Placement> {
+    let mut targets = cells.to_vec();
+    targets.sort();
+
+    let mut placement = Placement {
+      r#type,
+      direction,
+      x: 0,
+      y: 0,
+    };
+    let mut blocks = placement.blocks(system);
+    blocks.sort();
+    if targets.len() != blocks.len() {
+      return None;
+    }
+
+    placement.x = targets[0].0 - blocks[0].0;
+    placement.y = targets[0].1 - blocks[0].1;
+    let mut moved = placement.blocks(system);
+    moved.sort();
+
+    if moved == targets {
+      Some(placement)
+    } else {
+      None
+    }
+  }
+
+  pub fn convert(&self, from: CoordinateSystem, to: CoordinateSystem) -> Placement {
+    Placement::from_blocks(to, self.r#type, self.direction, &self.blocks(from))
+      .expect("Every system has the same shape for each rotation.")
+  }
+
+  pub fn from_current_tetromino(piece: &CurrentTetromino) -> Placement {
+    Placement {
+      r#type: piece.r#type,
+      direction: piece.direction,
+      x: piece.x,
+      y: piece.y,
+    }
+  }
+
+  pub fn to_current_tetromino(&self) -> CurrentTetromino {
+    CurrentTetromino {
+      r#type: self.r#type,
+      direction: self.direction,
+      x: self.x,
+      y: self.y,
+    }
+  }
+}
+
+fn rotation_centre_blocks(placement: &Placement) -> Vec<XY> {
+  let spawn: [XY; 4] = match placement.r#type {
+    Tetromino::I => [(0, 0), (-1, 0), (1, 0), (2, 0)],
+    Tetromino::J => [(0, 0), (-1, 0), (1, 0), (-1, 1)],
+    Tetromino::L => [(0, 0), (-1, 0), (1, 0), (1, 1)],
+    Tetromino::O => [(0, 0), (1, 0), (0, 1), (1, 1)],
+    Tetromino::S => [(0, 0), (-1, 0), (0, 1), (1, 1)],
+    Tetromino::T => [(0, 0), (-1, 0), (1, 0), (0, 1)],
+    Tetromino::Z => [(0, 0), (1, 0), (0, 1), (-1, 1)],
+  };
+
+  spawn
+    .iter()
+    .map(|&(x, y)| match placement.direction {
+      Direction::Up => (x, y),
+      Direction::Right => (y, -x),
+      Direction::Down => (-x, -y),
+      Direction::Left => (-y, x),
+    })
+    .map(|(x, y)| (placement.x + x, placement.y + y))
+    .collect()
+}
+
+pub fn fumen_cell(cell: FieldCellValue) -> i32 {
+  match cell {
+    FieldCellValue::None => 0,
+    FieldCellValue::I => 1,
+    FieldCellValue::J => 6,
+    FieldCellValue::L => 2,
+    FieldCellValue::O => 3,
+    FieldCellValue::S => 7,
+    FieldCellValue::T => 5,
+    FieldCellValue::Z => 4,
+    FieldCellValue::Garbage => 8,
+  }
+}
+
+pub fn from_fumen_cell(cell: i32) -> Option<FieldCellValue> {
+  match cell {
+    0 => Some(FieldCellValue::None),
+    1 => Some(FieldCellValue::I),
+    6 => Some(FieldCellValue::J),
+    2 => Some(FieldCellValue::L),
+    3 => Some(FieldCellValue::O),
+    7 => Some(FieldCellValue::S),
+    5 => Some(FieldCellValue::T),
+    4 => Some(FieldCellValue::Z),
+    8 => Some(FieldCellValue::Garbage),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tetfu::tetfu_decoder::TetfuDecoder;
+  use crate::tetsimu2::field::Field;
+  use libtetris::Piece;
+  use libtetris::PieceState;
+  use libtetris::RotationState;
+
+  const TYPES: [Tetromino; 7] = [
+    Tetromino::I,
+    Tetromino::J,
+    Tetromino::L,
+    Tetromino::O,
+    Tetromino::S,
+    Tetromino::T,
+    Tetromino::Z,
+  ];
+
+  const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+  ];
+
+  fn sorted(mut blocks: Vec<XY>) -> Vec<XY> {
+    blocks.sort();
+    blocks
+  }
+
+  #[test]
+  fn convert_to_libtetris_cells() {
+    for &r#type in &TYPES {
+      for &direction in &DIRECTIONS {
+        let placement = Placement {
+          r#type,
+          direction,
+          x: 4,
+          y: 5,
+        };
+        let libtetris = placement.convert(CoordinateSystem::Tetsimu2, CoordinateSystem::Libtetris);
+        let cells = PieceState(Piece::from(r#type), RotationState::from(direction))
+          .cells()
+          .iter()
+          .map(|&(x, y)| (libtetris.x + x, libtetris.y + y))
+          .collect();
+
+        assert_eq!(
+          sorted(cells),
+          sorted(placement.blocks(CoordinateSystem::Tetsimu2)),
+          "{:?} {:?}",
+          r#type,
+          direction
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn convert_to_fumen_cells() {
+    let placement = |r#type, direction, x, y| Placement {
+      r#type,
+      direction,
+      x,
+      y,
+    };
+    // Fields holding one piece per rotation, at the positions fumen reports for them.
+    let cases = [
+      (
+        "v115@0gwwHexwIewwBewwFewwBe0wAeywAewwBewwLeAgH",
+        FieldCellValue::T,
+        [
+          placement(Tetromino::T, Direction::Up, 1, 0),
+          placement(Tetromino::T, Direction::Right, 4, 1),
+          placement(Tetromino::T, Direction::Down, 7, 1),
+          placement(Tetromino::T, Direction::Left, 1, 3),
+        ],
+      ),
+      (
+        "v115@7gwhBewhFewhBewhAezhAewhBewhFewhBewhAezhMeAgH",
+        FieldCellValue::I,
+        [
+          placement(Tetromino::I, Direction::Up, 4, 0),
+          placement(Tetromino::I, Direction::Right, 1, 2),
+          placement(Tetromino::I, Direction::Down, 5, 2),
+          placement(Tetromino::I, Direction::Left, 8, 2),
+        ],
+      ),
+    ];
+
+    for (fumen, cell, placements) in &cases {
+      let decoded = TetfuDecoder::new().decode(String::from(*fumen)).unwrap();
+      let mut field = Field::new();
+      for placement in placements {
+        for (x, y) in placement.blocks(CoordinateSystem::Fumen) {
+          field.set_cell(x, y, *cell);
+        }
+      }
+      assert_eq!(decoded.field, field, "{}", fumen);
+    }
+  }
+
+  #[test]
+  fn convert_i() {
+    let i = |direction, x, y| Placement {
+      r#type: Tetromino::I,
+      direction,
+      x,
+      y,
+    };
+
+    let cases = [
+      (i(Direction::Up, 4, 0), i(Direction::Up, 4, 0)),
+      (i(Direction::Right, 9, 2), i(Direction::Right, 8, 2)),
+      (i(Direction::Down, 5, 0), i(Direction::Down, 4, 1)),
+      (i(Direction::Left, 0, 1), i(Direction::Left, 0, 2)),
+    ];
+    for (libtetris, tetsimu2) in &cases {
+      assert_eq!(
+        &libtetris.convert(CoordinateSystem::Libtetris, CoordinateSystem::Tetsimu2),
+        tetsimu2
+      );
+      assert_eq!(
+        &tetsimu2.convert(CoordinateSystem::Tetsimu2, CoordinateSystem::Libtetris),
+        libtetris
+      );
+    }
+  }
+
+  #[test]
+  fn fumen_cell() {
+    for v in 0..=8 {
+      assert_eq!(from_fumen_cell(v).map(super::fumen_cell), Some(v));
+    }
+    assert_eq!(from_fumen_cell(9), None);
+  }
+}
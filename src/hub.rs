@@ -1,6 +1,9 @@
+pub mod analysis_cache;
 pub mod hub_message_processor;
 pub mod hub_server;
 pub mod messages;
 pub mod processor_context;
 pub mod processors;
+pub mod semaphore;
+pub mod shared_context;
 pub mod tetsimu2_message_processor;
@@ -0,0 +1,402 @@
+use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBody;
+use crate::settings::CacheSettings;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+const INDEX_FILE: &str = "index.json";
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AnalysisCacheKey {
+  pub field: Vec<u8>,
+  pub patterns: String,
+  pub clear_line: i32,
+  pub use_hold: bool,
+  pub drop_type: String,
+  pub post_process: String,
+  pub sfinder_fingerprint: String,
+}
+
+impl AnalysisCacheKey {
+  pub fn new(
+    field: &[u8],
+    patterns: &str,
+    clear_line: i32,
+    use_hold: bool,
+    drop_type: &str,
+    post_process: &str,
+    sfinder_fingerprint: &str,
+  ) -> Self {
+    let mut field = field.to_vec();
+    while field.last() == Some(&0) {
+      field.pop();
+    }
+
+    let patterns = patterns
+      .split(',')
+      .map(|pattern| pattern.chars().filter(|c| !c.is_whitespace()).collect())
+      .filter(|pattern: &String| !pattern.is_empty())
+      .collect::<Vec<_>>()
+      .join(",");
+
+    AnalysisCacheKey {
+      field,
+      patterns,
+      clear_line,
+      use_hold,
+      drop_type: String::from(drop_type),
+      post_process: String::from(post_process),
+      sfinder_fingerprint: String::from(sfinder_fingerprint),
+    }
+  }
+
+  pub fn digest(&self) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CACHE_FORMAT_VERSION.to_le_bytes());
+    hasher.update(&self.field);
+    hasher.update([0xff]);
+    hasher.update(self.patterns.as_bytes());
+    hasher.update([0xff]);
+    hasher.update(self.clear_line.to_le_bytes());
+    hasher.update([self.use_hold as u8]);
+    hasher.update(self.drop_type.as_bytes());
+    hasher.update([0xff]);
+    hasher.update(self.post_process.as_bytes());
+    hasher.update([0xff]);
+    hasher.update(self.sfinder_fingerprint.as_bytes());
+    format!("{:x}", hasher.finalize())
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CacheIndex {
+  sfinder_fingerprint: String,
+  clock: u64,
+  entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CacheEntry {
+  size: u64,
+  last_used: u64,
+}
+
+struct JarFingerprint {
+  len: u64,
+  modified: Option<SystemTime>,
+  digest: String,
+}
+
+pub struct AnalysisCache {
+  dir: PathBuf,
+  max_entries: usize,
+  max_bytes: u64,
+  index: Mutex<CacheIndex>,
+  jar_fingerprint: Mutex<Option<JarFingerprint>>,
+}
+
+impl AnalysisCache {
+  pub fn open(dir: &Path, max_entries: usize, max_bytes: u64) -> Result<Self, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("{:#}", e))?;
+
+    let mut index = match fs::read_to_string(dir.join(INDEX_FILE)) {
+      Ok(json) => serde_json::from_str::<CacheIndex>(&json).unwrap_or_else(|e| {
+        warn!("Cache index is broken, so it is reset. {}", e);
+        CacheIndex::default()
+      }),
+      Err(_) => CacheIndex::default(),
+    };
+    index
+      .entries
+      .retain(|key, _| dir.join(format!("{}.json", key)).exists());
+
+    Ok(AnalysisCache {
+      dir: dir.to_path_buf(),
+      max_entries,
+      max_bytes,
+      index: Mutex::new(index),
+      jar_fingerprint: Mutex::new(None),
+    })
+  }
+
+  pub fn from_settings(settings: &CacheSettings) -> Option<Self> {
+    if !settings.enabled {
+      return None;
+    }
+
+    let dir = match &settings.path {
+      Some(path) => PathBuf::from(path),
+      None => std::env::temp_dir().join("tetsimu2_hub_cache"),
+    };
+
+    match AnalysisCache::open(
+      &dir,
+      settings.max_entries,
+      settings.max_size_mb * 1024 * 1024,
+    ) {
+      Ok(cache) => Some(cache),
+      Err(e) => {
+        warn!("Analysis cache is disabled. {}", e);
+        None
+      }
+    }
+  }
+
+  pub fn jar_fingerprint(&self, jar: &Path) -> Result<String, String> {
+    let metadata = fs::metadata(jar).map_err(|e| format!("{:#}", e))?;
+    let len = metadata.len();
+    let modified = metadata.modified().ok();
+
+    let mut fingerprint = self.jar_fingerprint.lock().unwrap();
+    if let Some(x) = fingerprint.as_ref() {
+      if x.len == len && x.modified == modified {
+        return Ok(x.digest.clone());
+      }
+    }
+
+    let bytes = fs::read(jar).map_err(|e| format!("{:#}", e))?;
+    let digest = format!("{:x}", Sha256::digest(&bytes));
+    *fingerprint = Some(JarFingerprint {
+      len,
+      modified,
+      digest: digest.clone(),
+    });
+
+    Ok(digest)
+  }
+
+  pub fn get(&self, key: &AnalysisCacheKey) -> Option<AnalyzePcMessageResBody> {
+    let mut index = self.index.lock().unwrap();
+    self.invalidate_if_sfinder_changed(&mut index, &key.sfinder_fingerprint);
+
+    let digest = key.digest();
+    if !index.entries.contains_key(&digest) {
+      return None;
+    }
+
+    let body = fs::read_to_string(self.entry_path(&digest))
+      .ok()
+      .and_then(|json| serde_json::from_str::<AnalyzePcMessageResBody>(&json).ok());
+
+    match body {
+      Some(body) => {
+        index.clock += 1;
+        let clock = index.clock;
+        index.entries.get_mut(&digest).unwrap().last_used = clock;
+        self.save_index(&index);
+        debug!("Cache hit. {}", digest);
+        Some(body)
+      }
+      None => {
+        self.remove_entry(&mut index, &digest);
+        self.save_index(&index);
+        None
+      }
+    }
+  }
+
+  pub fn put(&self, key: &AnalysisCacheKey, body: &AnalyzePcMessageResBody) {
+    let json = match serde_json::to_string(body) {
+      Ok(x) => x,
+      Err(e) => {
+        warn!("Failed to serialize cache entry. {}", e);
+        return;
+      }
+    };
+
+    let size = json.len() as u64;
+    if size > self.max_bytes || self.max_entries == 0 {
+      return;
+    }
+
+    let mut index = self.index.lock().unwrap();
+    self.invalidate_if_sfinder_changed(&mut index, &key.sfinder_fingerprint);
+
+    let digest = key.digest();
+    if let Err(e) = fs::write(self.entry_path(&digest), json) {
+      warn!("Failed to write cache entry. {}", e);
+      return;
+    }
+
+    index.clock += 1;
+    let last_used = index.clock;
+    index
+      .entries
+      .insert(digest.clone(), CacheEntry { size, last_used });
+    self.evict(&mut index);
+    self.save_index(&index);
+  }
+
+  fn invalidate_if_sfinder_changed(&self, index: &mut CacheIndex, fingerprint: &str) {
+    if index.sfinder_fingerprint == fingerprint {
+      return;
+    }
+
+    if !index.entries.is_empty() {
+      debug!("Solution finder has been changed, so cache is cleared.");
+    }
+
+    let keys: Vec<_> = index.entries.keys().cloned().collect();
+    for key in keys {
+      self.remove_entry(index, &key);
+    }
+    index.sfinder_fingerprint = String::from(fingerprint);
+    self.save_index(index);
+  }
+
+  fn evict(&self, index: &mut CacheIndex) {
+    loop {
+      let total_size: u64 = index.entries.values().map(|entry| entry.size).sum();
+      if index.entries.len() <= self.max_entries && total_size <= self.max_bytes {
+        break;
+      }
+
+      let oldest = index
+        .entries
+        .iter()
+        .min_by_key(|(_, entry)| entry.last_used)
+        .map(|(key, _)| key.clone());
+      match oldest {
+        Some(key) => self.remove_entry(index, &key),
+        None => break,
+      }
+    }
+  }
+
+  fn remove_entry(&self, index: &mut CacheIndex, key: &str) {
+    index.entries.remove(key);
+    fs::remove_file(self.entry_path(key)).ok();
+  }
+
+  fn save_index(&self, index: &CacheIndex) {
+    let result = serde_json::to_string(index)
+      .map_err(|e| e.to_string())
+      .and_then(|json| fs::write(self.dir.join(INDEX_FILE), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+      warn!("Failed to save cache index. {}", e);
+    }
+  }
+
+  fn entry_path(&self, key: &str) -> PathBuf {
+    self.dir.join(format!("{}.json", key))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use uuid::Uuid;
+
+  struct TestDir(PathBuf);
+
+  impl TestDir {
+    fn new() -> Self {
+      TestDir(
+        std::env::temp_dir()
+          .join("tetsimu2_hub_test")
+          .join(Uuid::new_v4().to_string()),
+      )
+    }
+  }
+
+  impl Drop for TestDir {
+    fn drop(&mut self) {
+      fs::remove_dir_all(&self.0).ok();
+    }
+  }
+
+  fn make_key(patterns: &str, fingerprint: &str) -> AnalysisCacheKey {
+    AnalysisCacheKey::new(
+      &[8, 8, 0, 0],
+      patterns,
+      4,
+      true,
+      "softdrop",
+      "",
+      fingerprint,
+    )
+  }
+
+  fn make_body(message: &str) -> AnalyzePcMessageResBody {
+    AnalyzePcMessageResBody {
+      succeeded: true,
+      message: String::from(message),
+      cached: false,
+      minimal_items: vec![],
+      unique_items: vec![],
+      chains: vec![],
+    }
+  }
+
+  #[test]
+  fn key_is_normalized() {
+    let key1 = AnalysisCacheKey::new(&[8, 0, 0], "T, *p7", 4, true, "softdrop", "", "a");
+    let key2 = AnalysisCacheKey::new(&[8], "T,*p7 ,", 4, true, "softdrop", "", "a");
+    assert_eq!(key1, key2);
+    assert_eq!(key1.digest(), key2.digest());
+
+    let key3 = AnalysisCacheKey::new(&[8], "T,*p7", 4, false, "softdrop", "", "a");
+    assert_ne!(key1.digest(), key3.digest());
+  }
+
+  #[test]
+  fn put_and_get() {
+    let dir = TestDir::new();
+    let cache = AnalysisCache::open(&dir.0, 10, 1024 * 1024).unwrap();
+
+    assert_eq!(cache.get(&make_key("*p7", "a")), None);
+    cache.put(&make_key("*p7", "a"), &make_body("found"));
+    assert_eq!(cache.get(&make_key("*p7", "a")), Some(make_body("found")));
+
+    let reopened = AnalysisCache::open(&dir.0, 10, 1024 * 1024).unwrap();
+    assert_eq!(
+      reopened.get(&make_key("*p7", "a")),
+      Some(make_body("found"))
+    );
+  }
+
+  #[test]
+  fn evict_least_recently_used() {
+    let dir = TestDir::new();
+    let cache = AnalysisCache::open(&dir.0, 2, 1024 * 1024).unwrap();
+
+    cache.put(&make_key("I", "a"), &make_body("I"));
+    cache.put(&make_key("O", "a"), &make_body("O"));
+    cache.get(&make_key("I", "a"));
+    cache.put(&make_key("T", "a"), &make_body("T"));
+
+    assert_eq!(cache.get(&make_key("I", "a")), Some(make_body("I")));
+    assert_eq!(cache.get(&make_key("O", "a")), None);
+    assert_eq!(cache.get(&make_key("T", "a")), Some(make_body("T")));
+  }
+
+  #[test]
+  fn evict_by_size() {
+    let dir = TestDir::new();
+    let size = serde_json::to_string(&make_body("I")).unwrap().len() as u64;
+    let cache = AnalysisCache::open(&dir.0, 10, size * 2).unwrap();
+
+    cache.put(&make_key("I", "a"), &make_body("I"));
+    cache.put(&make_key("O", "a"), &make_body("O"));
+    cache.put(&make_key("T", "a"), &make_body("T"));
+
+    assert_eq!(cache.get(&make_key("I", "a")), None);
+    assert_eq!(cache.get(&make_key("O", "a")), Some(make_body("O")));
+  }
+
+  #[test]
+  fn invalidate_when_sfinder_changed() {
+    let dir = TestDir::new();
+    let cache = AnalysisCache::open(&dir.0, 10, 1024 * 1024).unwrap();
+
+    cache.put(&make_key("I", "a"), &make_body("I"));
+    assert_eq!(cache.get(&make_key("I", "b")), None);
+    assert_eq!(cache.get(&make_key("I", "a")), None);
+  }
+}
@@ -6,6 +6,8 @@ use crate::hub::messages::hub::version::VersionMessage;
 use crate::hub::messages::hub::HubMessage;
 use crate::hub::messages::hub::VERSION;
 use crate::hub::messages::tetsimu2::Tetsimu2Message;
+use crate::hub::processors::analyze_pc::sfinder_probe::SfinderProbe;
+use crate::hub::shared_context::SharedContext;
 use crate::hub::tetsimu2_message_processor::Tetsimu2MessageProcessor;
 use crate::settings::Settings;
 use anyhow::Context;
@@ -22,6 +24,7 @@ pub struct HubServer {
   t2_t: Option<Sender<Tetsimu2Message>>,
   out: ws::Sender,
   settings: Arc<Settings>,
+  context: Arc<SharedContext>,
 }
 
 impl Handler for HubServer {
@@ -46,7 +49,12 @@ impl Handler for HubServer {
     }
 
     let (t2_t, t2_r) = mpsc::channel::<Tetsimu2Message>();
-    Tetsimu2MessageProcessor::start(t2_r, self.out.clone(), self.settings.clone());
+    Tetsimu2MessageProcessor::start(
+      t2_r,
+      self.out.clone(),
+      self.settings.clone(),
+      self.context.clone(),
+    );
     self.t2_t = Some(t2_t);
 
     Ok(())
@@ -84,12 +92,14 @@ impl Handler for HubServer {
 }
 
 impl HubServer {
-  pub fn listen(settings: Arc<Settings>) -> anyhow::Result<()> {
+  pub fn listen(settings: Arc<Settings>, probe: SfinderProbe) -> anyhow::Result<()> {
     let endpoint = format!("{}:{}", settings.hub.host, settings.hub.port);
+    let context = Arc::new(SharedContext::new(&settings, &probe));
     let v = listen(endpoint, move |out| HubServer {
       out,
       t2_t: None,
       settings: settings.clone(),
+      context: context.clone(),
     })?;
     Ok(v)
   }
@@ -1,15 +1,21 @@
 pub mod analyze_pc;
+pub mod analyze_tspin;
+pub mod grade;
 pub mod header;
 pub mod init_tutor;
 pub mod log;
+pub mod opener;
 pub mod steps;
 pub mod term_tutor;
 pub mod unhandled;
 pub mod version;
 
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageRes;
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageRes;
+use crate::hub::messages::hub::grade::GradeMessage;
 use crate::hub::messages::hub::init_tutor::InitTutorMessageRes;
 use crate::hub::messages::hub::log::LogMessage;
+use crate::hub::messages::hub::opener::OpenerMessage;
 use crate::hub::messages::hub::steps::StepsMessage;
 use crate::hub::messages::hub::term_tutor::TermTutorMessageRes;
 use crate::hub::messages::hub::unhandled::UnhandledMessage;
@@ -21,8 +27,11 @@ pub const VERSION: &str = "1.0.0";
 #[derive(Serialize, Debug, PartialEq, Eq)]
 pub enum HubMessage {
   AnalyzePc(AnalyzePcMessageRes),
+  AnalyzeTspin(AnalyzeTspinMessageRes),
+  Grade(GradeMessage),
   InitTutor(InitTutorMessageRes),
   Log(LogMessage),
+  Opener(OpenerMessage),
   Steps(StepsMessage),
   TermTutor(TermTutorMessageRes),
   Unhandled(UnhandledMessage),
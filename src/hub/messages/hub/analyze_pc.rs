@@ -1,5 +1,5 @@
 use crate::hub::messages::hub::header::HubMessageResHeader;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_big_array::big_array;
 
 big_array! { BigArray; 300 }
@@ -10,25 +10,64 @@ pub struct AnalyzePcMessageRes {
   pub body: AnalyzePcMessageResBody,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct AnalyzePcMessageResBody {
   pub succeeded: bool,
   pub message: String,
+  #[serde(default)]
+  pub cached: bool,
   pub minimal_items: Vec<AnalyzePcMessageResBodyItem>,
   pub unique_items: Vec<AnalyzePcMessageResBodyItem>,
+  #[serde(default)]
+  pub chains: Vec<AnalyzePcMessageResBodyChain>,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct AnalyzePcMessageResBodyItem {
   pub title: String,
   pub detail: Vec<AnalyzePcMessageResBodyItemDetail>,
 }
 
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct AnalyzePcMessageResBodyItemDetail {
   pub settles: String,
   #[serde(with = "BigArray")]
   pub field: [u8; 300],
+  #[serde(default)]
+  pub steps: Vec<AnalyzePcMessageResBodyItemStep>,
+  #[serde(default)]
+  pub reachable: bool,
+  // Steps need the order pieces come in, which is only known for a fixed queue.
+  #[serde(default)]
+  pub steps_computed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AnalyzePcMessageResBodyItemStep {
+  pub r#type: u8,
+  pub dir: u8,
+  pub x: i8,
+  pub y: i8,
+  pub hold: bool,
+  pub route: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct AnalyzePcMessageResBodyChain {
+  pub settles: String,
+  pub fumen: String,
+  pub next_patterns: String,
+  pub solution_count: usize,
+  pub success_count: usize,
+  pub message: String,
+  // Set when a piece of a partly known bag had to be held, so `next_patterns` may allow pieces that
+  // cannot come.
+  #[serde(default)]
+  pub approximate: bool,
+  // Set when the solver budget of the request ran out before this branch was fully analyzed.
+  #[serde(default)]
+  pub truncated: bool,
+  pub children: Vec<AnalyzePcMessageResBodyChain>,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -0,0 +1,83 @@
+use crate::hub::messages::hub::header::HubMessageResHeader;
+use crate::hub::messages::hub::steps::Step;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageRes {
+  pub header: HubMessageResHeader,
+  pub body: AnalyzeTspinMessageResBody,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageResBody {
+  pub succeeded: bool,
+  pub message: String,
+  pub slots: Vec<AnalyzeTspinMessageResBodySlot>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageResBodySlot {
+  pub kind: TspinKind,
+  pub step: Step,
+  pub lines: u32,
+  pub reachable: bool,
+  pub missing: Vec<AnalyzeTspinMessageResBodyCell>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageResBodyCell {
+  pub x: i8,
+  pub y: i8,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TspinKind {
+  Mini,
+  Tss,
+  Tsd,
+  Tst,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub enum AnalyzeTspinMessageResResult {
+  Succeeded = 0,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialize() {
+    let actual = serde_json::to_string(&AnalyzeTspinMessageRes {
+      header: HubMessageResHeader {
+        version: String::from("1.0.0"),
+        message_id: String::from("abcd"),
+        request_message_id: String::from("123"),
+        result: AnalyzeTspinMessageResResult::Succeeded as i32,
+      },
+      body: AnalyzeTspinMessageResBody {
+        succeeded: true,
+        message: String::from(""),
+        slots: vec![AnalyzeTspinMessageResBodySlot {
+          kind: TspinKind::Tsd,
+          step: Step {
+            r#type: 6,
+            dir: 2,
+            x: 2,
+            y: 1,
+          },
+          lines: 2,
+          reachable: true,
+          missing: vec![AnalyzeTspinMessageResBodyCell { x: 1, y: 2 }],
+        }],
+      },
+    })
+    .unwrap();
+
+    let expected = r#"{"header":{"version":"1.0.0","message_id":"abcd","request_message_id":"123","result":0},"body":{"succeeded":true,"message":"","slots":[{"kind":"tsd","step":{"type":6,"dir":2,"x":2,"y":1},"lines":2,"reachable":true,"missing":[{"x":1,"y":2}]}]}}"#;
+
+    assert_eq!(actual, expected);
+  }
+}
@@ -0,0 +1,82 @@
+use crate::hub::messages::hub::header::HubMessageHeader;
+use crate::hub::messages::hub::steps::ScoreSource;
+use crate::hub::messages::hub::steps::Step;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct GradeMessage {
+  pub header: HubMessageHeader,
+  pub body: GradeMessageBody,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct GradeMessageBody {
+  pub request_message_id: String,
+  pub verdict: GradeVerdict,
+  pub delta: i32,
+  pub score_source: ScoreSource,
+  pub played: GradePlacement,
+  pub suggested: GradePlacement,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct GradePlacement {
+  pub hold: bool,
+  pub score: i32,
+  pub step: Step,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeVerdict {
+  Best,
+  Good,
+  Inaccuracy,
+  Mistake,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialize() {
+    let actual = serde_json::to_string(&GradeMessage {
+      header: HubMessageHeader {
+        version: String::from("1.0.0"),
+        message_id: String::from("abcd"),
+      },
+      body: GradeMessageBody {
+        request_message_id: String::from("123"),
+        verdict: GradeVerdict::Inaccuracy,
+        delta: 150,
+        score_source: ScoreSource::BotEval,
+        played: GradePlacement {
+          hold: false,
+          score: -300,
+          step: Step {
+            r#type: 6,
+            dir: 2,
+            x: 4,
+            y: 1,
+          },
+        },
+        suggested: GradePlacement {
+          hold: true,
+          score: -150,
+          step: Step {
+            r#type: 1,
+            dir: 0,
+            x: 4,
+            y: 0,
+          },
+        },
+      },
+    })
+    .unwrap();
+
+    let expected = r#"{"header":{"version":"1.0.0","message_id":"abcd"},"body":{"request_message_id":"123","verdict":"inaccuracy","delta":150,"score_source":"bot_eval","played":{"hold":false,"score":-300,"step":{"type":6,"dir":2,"x":4,"y":1}},"suggested":{"hold":true,"score":-150,"step":{"type":1,"dir":0,"x":4,"y":0}}}}"#;
+
+    assert_eq!(actual, expected);
+  }
+}
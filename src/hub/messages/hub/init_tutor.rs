@@ -13,4 +13,7 @@ pub struct InitTutorMessageRes {
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
-pub struct InitTutorMessageResBody {}
+pub struct InitTutorMessageResBody {
+  pub succeeded: bool,
+  pub message: String,
+}
@@ -0,0 +1,56 @@
+use crate::hub::messages::hub::header::HubMessageHeader;
+use crate::settings::OpenerOption;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct OpenerMessage {
+  pub header: HubMessageHeader,
+  pub body: OpenerMessageBody,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct OpenerMessageBody {
+  pub request_message_id: String,
+  pub candidates: Vec<OpenerCandidate>,
+  pub deviated_from: Vec<OpenerOption>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct OpenerCandidate {
+  pub opener: OpenerOption,
+  pub variant: String,
+  pub placed: u32,
+  pub total: u32,
+  pub is_possible: bool,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialize() {
+    let actual = serde_json::to_string(&OpenerMessage {
+      header: HubMessageHeader {
+        version: String::from("1.0.0"),
+        message_id: String::from("abcd"),
+      },
+      body: OpenerMessageBody {
+        request_message_id: String::from("123"),
+        candidates: vec![OpenerCandidate {
+          opener: OpenerOption::Tki,
+          variant: String::from("TKI"),
+          placed: 2,
+          total: 6,
+          is_possible: false,
+        }],
+        deviated_from: vec![OpenerOption::Pco],
+      },
+    })
+    .unwrap();
+
+    let expected = r#"{"header":{"version":"1.0.0","message_id":"abcd"},"body":{"request_message_id":"123","candidates":[{"opener":"tki","variant":"TKI","placed":2,"total":6,"is_possible":false}],"deviated_from":["pco"]}}"#;
+
+    assert_eq!(actual, expected);
+  }
+}
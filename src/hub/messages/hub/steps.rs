@@ -1,4 +1,6 @@
 use crate::hub::messages::hub::header::HubMessageHeader;
+use crate::settings::OpenerOption;
+use crate::settings::TutorModeOption;
 use serde::Serialize;
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -11,6 +13,40 @@ pub struct StepsMessage {
 pub struct StepsMessageBody {
   pub request_message_id: String,
   pub steps: Vec<Step>,
+  pub candidates: Vec<StepsCandidate>,
+  pub score_source: ScoreSource,
+  pub depth: u32,
+  pub nodes: u32,
+  pub nodes_per_sec: u32,
+  pub elapsed_ms: u32,
+  pub plan_changed: bool,
+  pub is_final: bool,
+  pub mode: TutorModeOption,
+  pub book_line: Option<OpenerOption>,
+}
+
+// Cold clear only reports the line it chose, so only the bot's choice has a plan and a search
+// behind it. The other candidates are placements the hub lists itself: their steps hold the
+// placement alone and their depth and nodes are 0.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct StepsCandidate {
+  pub hold: bool,
+  // The evaluation named by `score_source` right after the placement, without any search.
+  // Candidates are ordered by it, so the bot's choice is not always first.
+  pub score: i32,
+  pub is_bot_choice: bool,
+  pub steps: Vec<Step>,
+  pub depth: u32,
+  pub nodes: u32,
+}
+
+// Where candidate and grade scores come from. A TBP bot does not share its evaluation, so its
+// placements are scored by the hub's own cold clear evaluation instead.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreSource {
+  BotEval,
+  HubStaticEval,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq, Clone)]
@@ -48,11 +84,33 @@ mod tests {
             y: 0,
           },
         ],
+        candidates: vec![StepsCandidate {
+          hold: true,
+          score: -120,
+          is_bot_choice: false,
+          steps: vec![Step {
+            r#type: 2,
+            dir: 3,
+            x: 8,
+            y: 0,
+          }],
+          depth: 0,
+          nodes: 0,
+        }],
+        score_source: ScoreSource::HubStaticEval,
+        depth: 5,
+        nodes: 1000,
+        nodes_per_sec: 2000,
+        elapsed_ms: 500,
+        plan_changed: true,
+        is_final: false,
+        mode: TutorModeOption::Opener,
+        book_line: Some(OpenerOption::DtCannon),
       },
     })
     .unwrap();
 
-    let expected = r#"{"header":{"version":"1.0.0","message_id":"abcd"},"body":{"request_message_id":"123","steps":[{"type":1,"dir":2,"x":3,"y":4},{"type":2,"dir":3,"x":8,"y":0}]}}"#;
+    let expected = r#"{"header":{"version":"1.0.0","message_id":"abcd"},"body":{"request_message_id":"123","steps":[{"type":1,"dir":2,"x":3,"y":4},{"type":2,"dir":3,"x":8,"y":0}],"candidates":[{"hold":true,"score":-120,"is_bot_choice":false,"steps":[{"type":2,"dir":3,"x":8,"y":0}],"depth":0,"nodes":0}],"score_source":"hub_static_eval","depth":5,"nodes":1000,"nodes_per_sec":2000,"elapsed_ms":500,"plan_changed":true,"is_final":false,"mode":"opener","book_line":"dt_cannon"}}"#;
 
     assert_eq!(actual, expected);
   }
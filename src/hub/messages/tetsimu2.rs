@@ -1,10 +1,14 @@
 pub mod analyze_pc;
+pub mod analyze_tspin;
+pub mod cancel_analyze_pc;
 pub mod header;
 pub mod init_tutor;
 pub mod notify_status;
 pub mod term_tutor;
 
 use crate::hub::messages::tetsimu2::analyze_pc::AnalyzePcMessageReq;
+use crate::hub::messages::tetsimu2::analyze_tspin::AnalyzeTspinMessageReq;
+use crate::hub::messages::tetsimu2::cancel_analyze_pc::CancelAnalyzePcMessageReq;
 use crate::hub::messages::tetsimu2::init_tutor::InitTutorMessageReq;
 use crate::hub::messages::tetsimu2::notify_status::NotifyStatusMessageReq;
 use crate::hub::messages::tetsimu2::term_tutor::TermTutorMessageReq;
@@ -13,6 +17,8 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 pub enum Tetsimu2Message {
   AnalyzePc(AnalyzePcMessageReq),
+  AnalyzeTspin(AnalyzeTspinMessageReq),
+  CancelAnalyzePc(CancelAnalyzePcMessageReq),
   NotifyStatus(NotifyStatusMessageReq),
   InitTutor(InitTutorMessageReq),
   TermTutor(TermTutorMessageReq),
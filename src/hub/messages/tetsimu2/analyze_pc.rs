@@ -1,5 +1,6 @@
 use crate::hub::messages::tetsimu2::header::Tetsimu2MessageHeader;
 use serde::Deserialize;
+use serde::Serialize;
 use serde_big_array::big_array;
 
 big_array! { BigArray; 300 }
@@ -18,6 +19,48 @@ pub struct AnalyzePcMessageReqBody {
   pub clear_line: u8,
   pub use_hold: bool,
   pub drop_type: u8,
+  #[serde(default)]
+  pub post_process: PostProcessOptions,
+  #[serde(default)]
+  pub chain: Option<ChainOptions>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(default)]
+pub struct PostProcessOptions {
+  pub sort_by: Vec<SolutionSortKey>,
+  pub without_hold: bool,
+  pub without_line_deletion: bool,
+  pub tspin_only: bool,
+  pub dedupe_mirrors: bool,
+  pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SolutionSortKey {
+  TSpin,
+  WithoutHold,
+  WithoutLineDeletion,
+  FewerPieces,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ChainOptions {
+  pub depth: u8,
+  pub max_branches: usize,
+  pub bag_offset: usize,
+}
+
+impl Default for ChainOptions {
+  fn default() -> Self {
+    ChainOptions {
+      depth: 1,
+      max_branches: 3,
+      bag_offset: 0,
+    }
+  }
 }
 
 #[cfg(test)]
@@ -86,9 +129,33 @@ mod tests {
         clear_line: 4,
         use_hold: true,
         drop_type: 0,
+        post_process: PostProcessOptions::default(),
+        chain: None,
       },
     };
 
     assert_eq!(actual, expected);
   }
+
+  #[test]
+  fn deserialize_post_process() {
+    let actual = serde_json::from_str::<PostProcessOptions>(
+      r#"
+    {
+      "sort_by": ["t_spin", "fewer_pieces"],
+      "without_hold": true,
+      "limit": 3
+    }"#,
+    )
+    .unwrap();
+
+    let expected = PostProcessOptions {
+      sort_by: vec![SolutionSortKey::TSpin, SolutionSortKey::FewerPieces],
+      without_hold: true,
+      limit: Some(3),
+      ..Default::default()
+    };
+
+    assert_eq!(actual, expected);
+  }
 }
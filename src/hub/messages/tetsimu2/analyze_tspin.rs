@@ -0,0 +1,48 @@
+use crate::hub::messages::tetsimu2::header::Tetsimu2MessageHeader;
+use serde::Deserialize;
+use serde_big_array::big_array;
+
+big_array! { BigArray; 300 }
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageReq {
+  pub header: Tetsimu2MessageHeader,
+  pub body: AnalyzeTspinMessageReqBody,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct AnalyzeTspinMessageReqBody {
+  #[serde(with = "BigArray")]
+  pub field: [u8; 300],
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn deserialize() {
+    let mut field = vec!["0"; 300];
+    field[0] = "8";
+    let json = format!(
+      r#"{{"header":{{"version":"1.0.0","message_id":"abcd"}},"body":{{"field":[{}]}}}}"#,
+      field.join(",")
+    );
+    let actual = serde_json::from_str::<AnalyzeTspinMessageReq>(&json).unwrap();
+
+    let mut expected_field = [0; 300];
+    expected_field[0] = 8;
+    assert_eq!(
+      actual,
+      AnalyzeTspinMessageReq {
+        header: Tetsimu2MessageHeader {
+          version: String::from("1.0.0"),
+          message_id: String::from("abcd"),
+        },
+        body: AnalyzeTspinMessageReqBody {
+          field: expected_field,
+        },
+      }
+    );
+  }
+}
@@ -0,0 +1,41 @@
+use crate::hub::messages::tetsimu2::header::Tetsimu2MessageHeader;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct CancelAnalyzePcMessageReq {
+  pub header: Tetsimu2MessageHeader,
+  pub body: CancelAnalyzePcMessageReqBody,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct CancelAnalyzePcMessageReqBody {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn deserialize() {
+    let actual = serde_json::from_str::<CancelAnalyzePcMessageReq>(
+      r#"
+    {
+      "header": {
+        "version": "1.0.0",
+        "message_id": "abcd"
+      },
+      "body": {}
+    }"#,
+    )
+    .unwrap();
+
+    let expected = CancelAnalyzePcMessageReq {
+      header: Tetsimu2MessageHeader {
+        version: String::from("1.0.0"),
+        message_id: String::from("abcd"),
+      },
+      body: CancelAnalyzePcMessageReqBody {},
+    };
+
+    assert_eq!(actual, expected);
+  }
+}
@@ -1,4 +1,5 @@
 use crate::hub::messages::tetsimu2::header::Tetsimu2MessageHeader;
+use crate::settings::TutorOptions;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -8,11 +9,19 @@ pub struct InitTutorMessageReq {
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
-pub struct InitTutorMessageReqBody {}
+pub struct InitTutorMessageReqBody {
+  #[serde(flatten)]
+  pub options: TutorOptions,
+}
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::settings::MovementModeOption;
+  use crate::settings::OpenerOption;
+  use crate::settings::PcLoopOption;
+  use crate::settings::TutorModeOption;
+  use crate::settings::WeightsPreset;
 
   #[test]
   fn deserialize() {
@@ -33,9 +42,45 @@ mod tests {
         version: String::from("1.0.0"),
         message_id: String::from("abcd"),
       },
-      body: InitTutorMessageReqBody {},
+      body: InitTutorMessageReqBody {
+        options: TutorOptions::default(),
+      },
     };
 
     assert_eq!(actual, expected);
   }
+
+  #[test]
+  fn deserialize_options() {
+    let actual = serde_json::from_str::<InitTutorMessageReqBody>(
+      r#"
+    {
+      "mode": "opener",
+      "opener": "dt_cannon",
+      "threads": 2,
+      "max_nodes": 10000,
+      "pc_loop": "fastest",
+      "movement_mode": "hard_drop_only",
+      "weights_preset": "tspin",
+      "weights": { "height": -80 }
+    }"#,
+    )
+    .unwrap();
+
+    assert_eq!(actual.options.mode, Some(TutorModeOption::Opener));
+    assert_eq!(actual.options.opener, Some(OpenerOption::DtCannon));
+    assert_eq!(actual.options.threads, Some(2));
+    assert_eq!(actual.options.min_nodes, None);
+    assert_eq!(actual.options.max_nodes, Some(10000));
+    assert_eq!(actual.options.pc_loop, Some(PcLoopOption::Fastest));
+    assert_eq!(
+      actual.options.movement_mode,
+      Some(MovementModeOption::HardDropOnly)
+    );
+    assert_eq!(actual.options.weights_preset, Some(WeightsPreset::Tspin));
+    assert_eq!(
+      actual.options.weights.unwrap()["height"],
+      serde_json::json!(-80)
+    );
+  }
 }
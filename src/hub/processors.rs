@@ -1,3 +1,4 @@
 pub mod analyze_pc;
+pub mod analyze_tspin;
 pub mod tetsimu2_processor;
 pub mod tutor;
@@ -1,7 +1,21 @@
+#[cfg(test)]
+pub mod fake_backend;
+pub mod native_backend;
+pub mod pc_chain;
+pub mod post_process;
+pub mod sfinder_backend;
+pub mod sfinder_csv;
+pub mod sfinder_features;
+pub mod sfinder_probe;
+pub mod solution_steps;
+pub mod solver_backend;
+
+use crate::hub::analysis_cache::AnalysisCacheKey;
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageRes;
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBody;
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBodyItem;
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBodyItemDetail;
+use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBodyItemStep;
 use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResResult;
 use crate::hub::messages::hub::header::HubMessageResHeader;
 use crate::hub::messages::hub::log::LogMessage;
@@ -9,73 +23,39 @@ use crate::hub::messages::hub::HubMessage;
 use crate::hub::messages::hub::VERSION;
 use crate::hub::messages::tetsimu2::analyze_pc::AnalyzePcMessageReq;
 use crate::hub::messages::tetsimu2::Tetsimu2Message;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverBackend;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverOutput;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverRequest;
+use crate::hub::processors::analyze_pc::solver_backend::SolveControl;
 use crate::hub::processors::tetsimu2_processor::BeforeExecuteResult;
 use crate::hub::processors::tetsimu2_processor::Tetsimu2Processor;
+use crate::hub::semaphore::SemaphoreGuard;
+use crate::hub::shared_context::SharedContext;
 use crate::settings::Settings;
-use crate::tetfu::core::Tetsimu2Content;
-use crate::tetfu::tetfu_decoder::TetfuDecoder;
-use crate::tetfu::tetfu_encoder::TetfuEncoder;
+use crate::tetsimu2::core::DropType;
 use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
 use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
 use crate::tetsimu2::core::MAX_FIELD_WIDTH;
 use crate::tetsimu2::field::Field;
 use anyhow::Context;
 use anyhow::Result;
 use core::convert::TryFrom;
-use log::{debug, info, warn};
+use log::{debug, info};
 use num_traits::FromPrimitive;
-use scraper::Selector;
 use std::convert::TryInto;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
-use std::path::PathBuf;
-use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use uuid::Uuid;
 
-const MAIN_JAR: &str = "sfinder.jar";
-const MINIMAL_FILE: &str = "output/path_minimal.html";
-const UNIQUE_FILE: &str = "output/path_unique.html";
-
-#[derive(Debug, PartialEq, Eq)]
-enum DropType {
-  SoftDrop = 0,
-  HardDrop = 1,
-  OneHundredEighty = 2,
-  TSoftDrop = 3,
-  AnyTSpin = 4,
-  Tss = 5,
-  Tsd = 6,
-  Tst = 7,
-}
-
-impl TryFrom<u8> for DropType {
-  type Error = String;
-
-  fn try_from(n: u8) -> Result<Self, Self::Error> {
-    let t = match n {
-      0 => DropType::SoftDrop,
-      1 => DropType::HardDrop,
-      2 => DropType::OneHundredEighty,
-      3 => DropType::TSoftDrop,
-      4 => DropType::AnyTSpin,
-      5 => DropType::Tss,
-      6 => DropType::Tsd,
-      7 => DropType::Tst,
-      _ => return Err(format!("Cannot convert from '{}' to DropType", n)),
-    };
-
-    Ok(t)
-  }
-}
-
 pub struct AnalyzePcProcesssor {
   out: ws::Sender,
-  settings: Arc<Settings>,
+  context: Arc<SharedContext>,
+  backend: Box<dyn PcSolverBackend>,
   is_done: AtomicBool,
+  is_halted: AtomicBool,
 }
 
 impl Tetsimu2Processor for AnalyzePcProcesssor {
@@ -88,16 +68,21 @@ impl Tetsimu2Processor for AnalyzePcProcesssor {
     }
   }
 
-  fn before_execute(&self, _message: &Tetsimu2Message) -> BeforeExecuteResult {
+  fn before_execute(&self, message: &Tetsimu2Message) -> BeforeExecuteResult {
     if self.is_done.load(Ordering::Relaxed) {
       BeforeExecuteResult::Done
     } else {
-      BeforeExecuteResult::Deny
+      match message {
+        Tetsimu2Message::CancelAnalyzePc(_) => BeforeExecuteResult::Halt,
+        _ => BeforeExecuteResult::Deny,
+      }
     }
   }
 
   fn halt(&self) {
     info!("Halt.");
+    self.is_halted.store(true, Ordering::Relaxed);
+    self.backend.halt();
   }
 }
 
@@ -109,263 +94,16 @@ impl AnalyzePcProcesssor {
   }
 
   fn execute_request(&self, message: &AnalyzePcMessageReq) -> ExecuteRequestResult {
-    let settings = self.settings.clone();
-    let sf_root = if let Some(x) = &settings.solution_finder.path {
-      x
-    } else {
-      return ExecuteRequestResult::OtherError(String::from(
-        "Solution finder settings is not set.",
-      ));
-    };
-
-    if !Path::new(&sf_root).join(MAIN_JAR).exists() {
-      return ExecuteRequestResult::OtherError(format!("Cannot find {}.", MAIN_JAR));
-    }
-
-    let data_vec = match message
-      .body
-      .field
-      .iter()
-      .map(|x| FromPrimitive::from_u8(*x).context("Could not '{}' to FieldCellValue."))
-      .collect::<Result<Vec<FieldCellValue>>>()
-    {
-      Ok(x) => x,
-      Err(e) => return ExecuteRequestResult::OtherError(format!("{:?}", e)),
-    };
-
-    let data: [FieldCellValue; 300] = match data_vec.try_into() {
-      Ok(x) => x,
-      Err(e) => return ExecuteRequestResult::OtherError(format!("{:?}", e)),
-    };
-
-    let field = Field { data };
-    debug!("field:\n {:?}", field);
-
-    let clear_line = if message.body.clear_line == 0 {
-      self.decide_clear_line(&field)
-    } else {
-      message.body.clear_line as i32
-    };
-    debug!("clear_line: {}", clear_line);
-    if clear_line == -1 {
-      return ExecuteRequestResult::OtherError(String::from("Empty cell must be multiples of 4"));
-    }
-
-    let use_hold = if message.body.use_hold {
-      "use"
-    } else {
-      "avoid"
-    };
-
-    let drop_type = match DropType::try_from(message.body.drop_type) {
-      Ok(drop_type) => match drop_type {
-        DropType::SoftDrop => "softdrop",
-        DropType::HardDrop => "harddrop",
-        DropType::Tss => "tss",
-        DropType::Tsd => "tsd",
-        DropType::Tst => "tst",
-        _ => {
-          return ExecuteRequestResult::OtherError(format!(
-            "Unsupported drop type passed({})",
-            drop_type as u8
-          ))
-        }
-      },
-      Err(e) => {
-        return ExecuteRequestResult::OtherError(String::from(e));
-      }
-    };
-
-    let tetfu_encoder = TetfuEncoder::new();
-    let tetfu = tetfu_encoder.encode(&Tetsimu2Content {
-      field,
-      comment: String::from(""),
-    });
-
-    let output = Command::new("java")
-      .arg("-jar")
-      .arg(MAIN_JAR)
-      .arg("path")
-      .arg("--tetfu")
-      .arg(tetfu)
-      .arg("--patterns")
-      .arg(&message.body.nexts)
-      .arg("--clear-line")
-      .arg(clear_line.to_string())
-      .arg("--hold")
-      .arg(use_hold)
-      .arg("--drop")
-      .arg(drop_type)
-      .arg("--format")
-      .arg("html")
-      .current_dir(settings.solution_finder.path.as_ref().unwrap())
-      .output();
-
-    self.log("Analyzing...");
-
-    let output = match output {
-      Ok(x) => x,
-      Err(e) => {
-        return ExecuteRequestResult::OtherError(e.to_string());
-      }
-    };
-
-    debug!("status: {}", output.status);
-
-    if output.status.success() {
-      let stdout = String::from_utf8_lossy(&output.stdout);
-      debug!("stdout:\n{}", &stdout);
-      match self.create_response_body(&stdout) {
-        Ok(body) => {
-          return ExecuteRequestResult::Succeeded(body);
-        }
-        Err(e) => {
-          return ExecuteRequestResult::OtherError(e);
-        }
-      };
-    } else {
-      let err_message = String::from_utf8_lossy(&output.stderr);
-      warn!("stderr:\n{}", err_message);
-
-      let lines = err_message.split("\n").map(|s| s.trim());
-      for line in lines {
-        if line.starts_with("Message: ") {
-          return ExecuteRequestResult::OtherError(String::from(&line["Message: ".len()..]));
-        }
-      }
-
-      return ExecuteRequestResult::OtherError(String::from("Failed to analyze."));
-    }
-  }
-
-  fn decide_clear_line(&self, field: &Field) -> i32 {
-    let mut tmp_clear_line = 4;
-
-    'outer: for y in (0..MAX_FIELD_HEIGHT).rev() {
-      for x in 0..MAX_FIELD_WIDTH {
-        if field.get_cell(x, y) != FieldCellValue::None {
-          tmp_clear_line = y + 1;
-          break 'outer;
-        }
-      }
-    }
-
-    let mut empty_cell_num = 0;
-    for y in 0..tmp_clear_line {
-      for x in 0..MAX_FIELD_WIDTH {
-        if field.get_cell(x, y) == FieldCellValue::None {
-          empty_cell_num += 1;
-        }
-      }
-    }
-
-    debug!("empty_cell_num: {}", empty_cell_num);
-
-    if empty_cell_num % 2 == 1 {
-      return -1;
-    }
-
-    if empty_cell_num % 4 == 0 {
-      return tmp_clear_line;
-    } else {
-      return tmp_clear_line + 1;
-    }
-  }
-
-  fn create_response_body(&self, stdout: &str) -> Result<AnalyzePcMessageResBody, String> {
-    let message = self.analyze_path_nums(stdout);
-
-    let settings = self.settings.clone();
-    let sf_root = settings.solution_finder.path.as_ref().unwrap();
-    let minimal_items = self.read_html(Path::new(&sf_root).join(MINIMAL_FILE))?;
-    let unique_items = self.read_html(Path::new(&sf_root).join(UNIQUE_FILE))?;
-
-    Ok(AnalyzePcMessageResBody {
-      succeeded: true,
-      message,
-      minimal_items,
-      unique_items,
-    })
-  }
-
-  fn read_html(&self, path: PathBuf) -> Result<Vec<AnalyzePcMessageResBodyItem>, String> {
-    let mut f = match File::open(&path) {
-      Ok(file) => file,
-      Err(_) => {
-        return Err(format!(
-          "File({}) cannot be opened.",
-          path.into_os_string().into_string().unwrap()
-        ))
-      }
+    let log = |m: &str| self.log(m);
+    let control = SolveControl {
+      is_halted: &self.is_halted,
+      log: &log,
     };
 
-    let mut contents = String::new();
-    if let Err(e) = f.read_to_string(&mut contents) {
-      return Err(e.to_string());
+    match analyze(message, self.backend.as_ref(), &self.context, &control) {
+      Ok(body) => ExecuteRequestResult::Succeeded(body),
+      Err(e) => ExecuteRequestResult::OtherError(e),
     }
-
-    let not_deleted_selector = Selector::parse("#notdeletedline a").unwrap();
-    let deleted_line_selector = Selector::parse("#deletedline a").unwrap();
-
-    let doc = scraper::Html::parse_document(&contents);
-    let not_deleted_details = self.read_path_doc(&doc, &not_deleted_selector)?;
-    let deleted_details = self.read_path_doc(&doc, &deleted_line_selector)?;
-
-    let mut items = vec![];
-    if !not_deleted_details.is_empty() {
-      items.push(AnalyzePcMessageResBodyItem {
-        title: String::from("Without line deletion"),
-        detail: not_deleted_details,
-      });
-    }
-
-    if !deleted_details.is_empty() {
-      items.push(AnalyzePcMessageResBodyItem {
-        title: String::from("With line deletion"),
-        detail: deleted_details,
-      });
-    }
-
-    Ok(items)
-  }
-
-  fn read_path_doc(
-    &self,
-    doc: &scraper::Html,
-    selector: &scraper::Selector,
-  ) -> Result<Vec<AnalyzePcMessageResBodyItemDetail>, String> {
-    let mut details = vec![];
-
-    for anchor in doc.select(&selector) {
-      let elem = anchor.value();
-      let href = elem.attr("href").unwrap();
-      let text = anchor.text().next().unwrap();
-      let settles = text
-        .split(' ')
-        .map(|x| x.chars().next().unwrap())
-        .collect::<String>();
-
-      let decoder = TetfuDecoder::new();
-      let tetsimu2_content = decoder.decode(href.to_string())?;
-      details.push(AnalyzePcMessageResBodyItemDetail {
-        settles,
-        field: tetsimu2_content.field.data.map(|x| x as u8),
-      });
-    }
-
-    Ok(details)
-  }
-
-  fn analyze_path_nums(&self, stdout: &str) -> String {
-    let mut found_paths = vec![];
-    let lines = stdout.split("\n").map(|s| s.trim());
-    for line in lines {
-      if line.starts_with("Found path") {
-        found_paths.push(line);
-      }
-    }
-
-    found_paths.join("\n")
   }
 
   fn execute_response(&self, request_result: ExecuteRequestResult, request: &AnalyzePcMessageReq) {
@@ -420,8 +158,10 @@ impl AnalyzePcProcesssor {
       body: AnalyzePcMessageResBody {
         succeeded: false,
         message: String::from(message),
+        cached: false,
         minimal_items: vec![],
         unique_items: vec![],
+        chains: vec![],
       },
     });
 
@@ -433,14 +173,263 @@ impl AnalyzePcProcesssor {
   }
 
   fn log(&self, message: &str) {
-    let log = LogMessage::create(message);
-    let message = HubMessage::Log(log);
+    send_log(&self.out, message);
+  }
+}
+
+pub fn analyze(
+  message: &AnalyzePcMessageReq,
+  backend: &dyn PcSolverBackend,
+  context: &SharedContext,
+  control: &SolveControl<'_>,
+) -> Result<AnalyzePcMessageResBody, String> {
+  let request = create_solver_request(message)?;
+  backend.validate(&request)?;
+
+  let cache_key = create_cache_key(message, &request, backend, context);
+  if let Some(body) = find_cache(&cache_key, context, control) {
+    return Ok(body);
+  }
+
+  let _slot = match acquire_analysis_slot(context, control) {
+    Some(slot) => slot,
+    None => return Err(String::from("Analysis was canceled.")),
+  };
+
+  control.log("Analyzing...");
+  let output = backend.solve(&request, control)?;
+  debug!("output:\n{}", output.message);
+
+  let chains = match &message.body.chain {
+    Some(options) => {
+      let queue = post_process::fixed_queue(&message.body.nexts);
+      let solutions = post_process::post_process(
+        output.unique.clone(),
+        queue.as_deref(),
+        &message.body.post_process,
+      );
+      pc_chain::analyze_chains(&solutions, &request, options, backend, control)?
+    }
+    None => vec![],
+  };
+
+  let mut body = create_response_body(output, message);
+  body.chains = chains;
+  if let (Some(cache), Some(key)) = (&context.analysis_cache, &cache_key) {
+    cache.put(key, &body);
+  }
+
+  Ok(body)
+}
+
+fn create_solver_request(message: &AnalyzePcMessageReq) -> Result<PcSolverRequest, String> {
+  let data_vec = message
+    .body
+    .field
+    .iter()
+    .map(|x| FromPrimitive::from_u8(*x).context("Could not '{}' to FieldCellValue."))
+    .collect::<Result<Vec<FieldCellValue>>>()
+    .map_err(|e| format!("{:?}", e))?;
+
+  let data: [FieldCellValue; 300] = data_vec.try_into().map_err(|e| format!("{:?}", e))?;
+
+  let field = Field { data };
+  debug!("field:\n {:?}", field);
+
+  let clear_line = if message.body.clear_line == 0 {
+    decide_clear_line(&field)
+  } else {
+    message.body.clear_line as i32
+  };
+  debug!("clear_line: {}", clear_line);
+  if clear_line == -1 {
+    return Err(String::from("Empty cell must be multiples of 4"));
+  }
+
+  let drop_type = DropType::try_from(message.body.drop_type)?;
+
+  Ok(PcSolverRequest {
+    field,
+    nexts: message.body.nexts.clone(),
+    clear_line,
+    use_hold: message.body.use_hold,
+    drop_type,
+  })
+}
+
+fn create_cache_key(
+  message: &AnalyzePcMessageReq,
+  request: &PcSolverRequest,
+  backend: &dyn PcSolverBackend,
+  context: &SharedContext,
+) -> Option<AnalysisCacheKey> {
+  context.analysis_cache.as_ref()?;
+  let fingerprint = backend.fingerprint()?;
+
+  Some(AnalysisCacheKey::new(
+    &message.body.field,
+    &message.body.nexts,
+    request.clear_line,
+    message.body.use_hold,
+    sfinder_features::drop_option(request.drop_type),
+    // Canonical JSON, so the key does not follow changes to the Debug output.
+    &serde_json::to_string(&(&message.body.post_process, &message.body.chain)).unwrap(),
+    &fingerprint,
+  ))
+}
+
+fn find_cache(
+  key: &Option<AnalysisCacheKey>,
+  context: &SharedContext,
+  control: &SolveControl<'_>,
+) -> Option<AnalyzePcMessageResBody> {
+  let cache = context.analysis_cache.as_ref()?;
+  let mut body = cache.get(key.as_ref()?)?;
+  body.cached = true;
+  control.log("Analysis result is found in cache.");
+  Some(body)
+}
+
+fn acquire_analysis_slot<'a>(
+  context: &'a SharedContext,
+  control: &SolveControl<'_>,
+) -> Option<SemaphoreGuard<'a>> {
+  let slots = &context.analysis_slots;
+  if let Some(slot) = slots.try_acquire() {
+    return Some(slot);
+  }
 
-    if let Ok(json) = serde_json::to_string(&message) {
-      debug!("response:\n{}", json);
-      self.out.send(json).ok();
+  control.log("Waiting for other analyses to finish...");
+  slots.acquire_unless(|| control.is_halted())
+}
+
+fn decide_clear_line(field: &Field) -> i32 {
+  let mut tmp_clear_line = 4;
+
+  'outer: for y in (0..MAX_FIELD_HEIGHT).rev() {
+    for x in 0..MAX_FIELD_WIDTH {
+      if field.get_cell(x, y) != FieldCellValue::None {
+        tmp_clear_line = y + 1;
+        break 'outer;
+      }
     }
   }
+
+  let mut empty_cell_num = 0;
+  for y in 0..tmp_clear_line {
+    for x in 0..MAX_FIELD_WIDTH {
+      if field.get_cell(x, y) == FieldCellValue::None {
+        empty_cell_num += 1;
+      }
+    }
+  }
+
+  debug!("empty_cell_num: {}", empty_cell_num);
+
+  if empty_cell_num % 2 == 1 {
+    return -1;
+  }
+
+  if empty_cell_num % 4 == 0 {
+    tmp_clear_line
+  } else {
+    tmp_clear_line + 1
+  }
+}
+
+fn create_response_body(
+  output: PcSolverOutput,
+  request: &AnalyzePcMessageReq,
+) -> AnalyzePcMessageResBody {
+  AnalyzePcMessageResBody {
+    succeeded: true,
+    message: output.message,
+    cached: false,
+    minimal_items: create_items(output.minimal, request),
+    unique_items: create_items(output.unique, request),
+    chains: vec![],
+  }
+}
+
+fn create_items(
+  solutions: Vec<SfinderSolution>,
+  request: &AnalyzePcMessageReq,
+) -> Vec<AnalyzePcMessageResBodyItem> {
+  let queue = post_process::fixed_queue(&request.body.nexts);
+  let solutions =
+    post_process::post_process(solutions, queue.as_deref(), &request.body.post_process);
+  group_solutions(solutions, queue.as_deref(), request)
+}
+
+fn group_solutions(
+  solutions: Vec<SfinderSolution>,
+  queue: Option<&[Tetromino]>,
+  request: &AnalyzePcMessageReq,
+) -> Vec<AnalyzePcMessageResBodyItem> {
+  let (deleted_solutions, not_deleted_solutions): (Vec<_>, Vec<_>) =
+    solutions.into_iter().partition(|s| s.deletes_line);
+
+  let drop_type = DropType::try_from(request.body.drop_type).unwrap_or(DropType::SoftDrop);
+  let to_details = |solutions: Vec<SfinderSolution>| -> Vec<AnalyzePcMessageResBodyItemDetail> {
+    solutions
+      .into_iter()
+      .map(|solution| {
+        let steps = queue.map(|queue| {
+          solution_steps::solution_steps(&solution, queue, request.body.use_hold, drop_type)
+        });
+
+        AnalyzePcMessageResBodyItemDetail {
+          settles: solution.settles(),
+          field: solution.field.data.map(|x| x as u8),
+          reachable: steps.as_ref().map_or(false, |steps| steps.reachable),
+          steps_computed: steps.is_some(),
+          steps: steps
+            .map(|steps| steps.steps)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|step| AnalyzePcMessageResBodyItemStep {
+              r#type: step.piece.r#type as u8,
+              dir: step.piece.direction as u8,
+              x: step.piece.x as i8,
+              y: step.piece.y as i8,
+              hold: step.hold,
+              route: step.route.into_iter().map(|action| action as u8).collect(),
+            })
+            .collect(),
+        }
+      })
+      .collect()
+  };
+
+  let not_deleted_details = to_details(not_deleted_solutions);
+  let deleted_details = to_details(deleted_solutions);
+
+  let mut items = vec![];
+  if !not_deleted_details.is_empty() {
+    items.push(AnalyzePcMessageResBodyItem {
+      title: String::from("Without line deletion"),
+      detail: not_deleted_details,
+    });
+  }
+
+  if !deleted_details.is_empty() {
+    items.push(AnalyzePcMessageResBodyItem {
+      title: String::from("With line deletion"),
+      detail: deleted_details,
+    });
+  }
+
+  items
+}
+
+fn send_log(out: &ws::Sender, message: &str) {
+  let log = LogMessage::create(message);
+  let message = HubMessage::Log(log);
+
+  if let Ok(json) = serde_json::to_string(&message) {
+    debug!("response:\n{}", json);
+    out.send(json).ok();
+  }
 }
 
 enum ExecuteRequestResult {
@@ -452,11 +441,14 @@ pub fn execute(
   out: &ws::Sender,
   message: AnalyzePcMessageReq,
   settings: &Arc<Settings>,
+  context: &Arc<SharedContext>,
 ) -> Arc<AnalyzePcProcesssor> {
   let processor = Arc::new(AnalyzePcProcesssor {
     out: out.clone(),
-    settings: settings.clone(),
+    context: context.clone(),
+    backend: solver_backend::create_backend(settings, context),
     is_done: AtomicBool::from(false),
+    is_halted: AtomicBool::from(false),
   });
 
   let processor2 = Arc::clone(&processor);
@@ -466,3 +458,180 @@ pub fn execute(
 
   processor
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::hub::messages::tetsimu2::analyze_pc::AnalyzePcMessageReqBody;
+  use crate::hub::messages::tetsimu2::analyze_pc::ChainOptions;
+  use crate::hub::messages::tetsimu2::header::Tetsimu2MessageHeader;
+  use crate::hub::processors::analyze_pc::fake_backend::FakeBackend;
+  use crate::hub::semaphore::Semaphore;
+  use std::sync::Mutex;
+
+  fn make_request(rows: &[&str], nexts: &str, drop_type: u8) -> AnalyzePcMessageReq {
+    AnalyzePcMessageReq {
+      header: Tetsimu2MessageHeader {
+        version: String::from("1.0.0"),
+        message_id: String::from("abcd"),
+      },
+      body: AnalyzePcMessageReqBody {
+        field: make_field(&rows.concat()).data.map(|x| x as u8),
+        nexts: String::from(nexts),
+        clear_line: 0,
+        use_hold: true,
+        drop_type,
+        post_process: Default::default(),
+        chain: None,
+      },
+    }
+  }
+
+  fn run(
+    backend: &FakeBackend,
+    request: &AnalyzePcMessageReq,
+  ) -> (Result<AnalyzePcMessageResBody, String>, Vec<String>) {
+    let context = SharedContext {
+      analysis_slots: Semaphore::new(1),
+      analysis_cache: None,
+      sfinder_features: Mutex::new(None),
+    };
+    let is_halted = AtomicBool::new(false);
+    let logs = Mutex::new(vec![]);
+    let log = |m: &str| logs.lock().unwrap().push(String::from(m));
+    let control = SolveControl {
+      is_halted: &is_halted,
+      log: &log,
+    };
+
+    let result = analyze(request, backend, &context, &control);
+    (result, logs.into_inner().unwrap())
+  }
+
+  #[test]
+  fn decide_clear_line() {
+    assert_eq!(super::decide_clear_line(&make_field("NNNNGGGGGG")), 1);
+    assert_eq!(super::decide_clear_line(&make_field("NNGGGGGGGG")), 2);
+    assert_eq!(super::decide_clear_line(&make_field("NGGGGGGGGG")), -1);
+    assert_eq!(super::decide_clear_line(&Field::new()), 4);
+  }
+
+  #[test]
+  fn analyze_should_validate_request() {
+    let backend = FakeBackend::new("solved");
+
+    let (result, _) = run(&backend, &make_request(&["NGGGGGGGGG"], "I", 0));
+    assert_eq!(
+      result,
+      Err(String::from("Empty cell must be multiples of 4"))
+    );
+
+    let (result, _) = run(&backend, &make_request(&["NNNNGGGGGG"], "I", 8));
+    assert_eq!(result.is_err(), true);
+    assert_eq!(backend.requests.lock().unwrap().len(), 0);
+  }
+
+  #[test]
+  fn analyze_should_pass_request_to_backend() {
+    let backend = FakeBackend::new("solved");
+    let (result, _) = run(&backend, &make_request(&["NNNNGGGGGG"], "I", 2));
+    assert_eq!(result.is_ok(), true);
+
+    let requests = backend.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].nexts, "I");
+    assert_eq!(requests[0].clear_line, 1);
+    assert_eq!(requests[0].use_hold, true);
+    assert_eq!(requests[0].drop_type, DropType::OneHundredEighty);
+  }
+
+  #[test]
+  fn analyze_should_build_response() {
+    let backend = FakeBackend::new("solved");
+    let (result, logs) = run(&backend, &make_request(&["NNNNGGGGGG"], "I", 0));
+    let body = result.unwrap();
+
+    assert_eq!(body.succeeded, true);
+    assert_eq!(body.cached, false);
+    assert_eq!(
+      body.message,
+      "Found path [unique] = 1\nFound path [minimal] = 1"
+    );
+    assert_eq!(body.minimal_items.len(), 1);
+    assert_eq!(body.minimal_items[0].title, "Without line deletion");
+
+    let detail = &body.unique_items[0].detail[0];
+    assert_eq!(detail.settles, "I");
+    assert_eq!(detail.reachable, true);
+    assert_eq!(detail.steps_computed, true);
+    assert_eq!(detail.steps.len(), 1);
+    assert_eq!(detail.steps[0].r#type, Tetromino::I as u8);
+
+    assert_eq!(logs[0], "Analyzing...");
+    assert_eq!(
+      logs.contains(&String::from("Found path [unique] = 1")),
+      true
+    );
+  }
+
+  #[test]
+  fn analyze_should_not_compute_steps_without_fixed_queue() {
+    let backend = FakeBackend::new("solved");
+    let (result, _) = run(&backend, &make_request(&["NNNNGGGGGG"], "*p1", 0));
+
+    let detail = &result.unwrap().unique_items[0].detail[0];
+    assert_eq!(detail.settles, "I");
+    assert_eq!(detail.steps_computed, false);
+    assert_eq!(detail.reachable, false);
+    assert_eq!(detail.steps.len(), 0);
+  }
+
+  #[test]
+  fn analyze_should_chain_next_pc() {
+    let backend = FakeBackend::new("solved");
+    let mut request = make_request(&["NNNNGGGGGG"], "I", 0);
+    request.body.chain = Some(ChainOptions::default());
+
+    let (result, _) = run(&backend, &request);
+    let chains = result.unwrap().chains;
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[0].settles, "I");
+    assert_eq!(chains[0].next_patterns, "[JLOSTZ]p6,*p5");
+    assert_eq!(chains[0].solution_count, 1);
+    assert_eq!(chains[0].success_count, 1);
+    assert_eq!(chains[0].children[0].children.len(), 0);
+
+    let requests = backend.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].field, Field::new());
+    assert_eq!(requests[1].clear_line, 4);
+  }
+
+  #[test]
+  fn analyze_should_reject_chain_without_fixed_queue() {
+    let backend = FakeBackend::new("solved");
+    let mut request = make_request(&["NNNNGGGGGG"], "*p7", 0);
+    request.body.chain = Some(ChainOptions::default());
+
+    let (result, _) = run(&backend, &request);
+    assert_eq!(
+      result,
+      Err(String::from(
+        "Chain analysis requires a fixed sequence of pieces."
+      ))
+    );
+  }
+
+  #[test]
+  fn analyze_should_map_solver_error() {
+    let backend = FakeBackend::new("failed");
+    let (result, _) = run(&backend, &make_request(&["NNNNGGGGGG"], "", 0));
+    assert_eq!(
+      result,
+      Err(String::from(
+        "Should specify equal to or more than 1 piece(s) in patterns"
+      ))
+    );
+  }
+}
@@ -0,0 +1,65 @@
+use crate::hub::processors::analyze_pc::sfinder_backend::error_message;
+use crate::hub::processors::analyze_pc::sfinder_backend::read_output;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverBackend;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverOutput;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverRequest;
+use crate::hub::processors::analyze_pc::solver_backend::SolveControl;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const FIXTURE_DIR: &str = "tests/fixtures/analyze_pc";
+
+// Replays what sfinder left in a fixture directory:
+//   stdout.txt, path_minimal.csv, path_unique.csv : a successful run
+//   stderr.txt                                    : a failed run
+pub struct FakeBackend {
+  fixture: PathBuf,
+  pub requests: Mutex<Vec<PcSolverRequest>>,
+}
+
+impl FakeBackend {
+  pub fn new(name: &str) -> Self {
+    FakeBackend {
+      fixture: Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(FIXTURE_DIR)
+        .join(name),
+      requests: Mutex::new(vec![]),
+    }
+  }
+}
+
+impl PcSolverBackend for FakeBackend {
+  fn validate(&self, _request: &PcSolverRequest) -> Result<(), String> {
+    if self.fixture.exists() {
+      Ok(())
+    } else {
+      Err(format!("Fixture {:?} is not found.", self.fixture))
+    }
+  }
+
+  fn fingerprint(&self) -> Option<String> {
+    Some(String::from("fake"))
+  }
+
+  fn solve(
+    &self,
+    request: &PcSolverRequest,
+    control: &SolveControl<'_>,
+  ) -> Result<PcSolverOutput, String> {
+    self.requests.lock().unwrap().push(request.clone());
+
+    if let Ok(stderr) = fs::read_to_string(self.fixture.join("stderr.txt")) {
+      return Err(error_message(&stderr));
+    }
+
+    let stdout = fs::read_to_string(self.fixture.join("stdout.txt")).map_err(|e| e.to_string())?;
+    stdout
+      .lines()
+      .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+      .for_each(|line| control.log(line));
+
+    read_output(&self.fixture, &stdout)
+  }
+}
@@ -0,0 +1,319 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::analyze_pc::post_process::fixed_queue;
+use crate::hub::processors::analyze_pc::post_process::placement_key;
+use crate::hub::processors::analyze_pc::sfinder_csv::deletes_line_before_last;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderOperation;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::processors::analyze_pc::solution_steps::spawn;
+use crate::hub::processors::analyze_pc::solution_steps::Hand;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverBackend;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverOutput;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverRequest;
+use crate::hub::processors::analyze_pc::solver_backend::SolveControl;
+use crate::tetfu::core::Tetsimu2Content;
+use crate::tetfu::tetfu_encoder::TetfuEncoder;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::core::XY;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::placements::placements;
+use crate::tetsimu2::router::RouteSearcher;
+use std::collections::HashSet;
+
+const NATIVE_FINGERPRINT: &str = "native-1";
+const MAX_SOLUTIONS: usize = 1000;
+
+pub struct NativeBackend;
+
+impl PcSolverBackend for NativeBackend {
+  fn validate(&self, request: &PcSolverRequest) -> Result<(), String> {
+    match fixed_queue(&request.nexts) {
+      Some(queue) if !queue.is_empty() => Ok(()),
+      _ => Err(String::from(
+        "Native solver supports only a fixed sequence of pieces.",
+      )),
+    }
+  }
+
+  fn fingerprint(&self) -> Option<String> {
+    Some(String::from(NATIVE_FINGERPRINT))
+  }
+
+  fn solve(
+    &self,
+    request: &PcSolverRequest,
+    control: &SolveControl<'_>,
+  ) -> Result<PcSolverOutput, String> {
+    let queue = fixed_queue(&request.nexts).unwrap_or_default();
+    let mut solver = NativeSolver {
+      request,
+      queue: &queue,
+      control,
+      solutions: vec![],
+      keys: HashSet::new(),
+    };
+
+    let rows: Vec<i32> = (0..MAX_FIELD_HEIGHT).collect();
+    solver.search(
+      &request.field,
+      &rows,
+      &Hand::new(&queue),
+      false,
+      &mut vec![],
+    );
+    if control.is_halted() {
+      return Err(String::from("Analysis was canceled."));
+    }
+
+    let unique = solver.solutions;
+    let minimal: Vec<_> = unique.iter().take(1).cloned().collect();
+    Ok(PcSolverOutput {
+      message: format!(
+        "Found path [unique] = {}\nFound path [minimal] = {}",
+        unique.len(),
+        minimal.len()
+      ),
+      minimal,
+      unique,
+    })
+  }
+}
+
+// The search runs on the field with lines cleared. `rows` maps each remaining row to its row in
+// the request field, so that operations can be reported the way sfinder does.
+struct NativeSolver<'a> {
+  request: &'a PcSolverRequest,
+  queue: &'a [Tetromino],
+  control: &'a SolveControl<'a>,
+  solutions: Vec<SfinderSolution>,
+  keys: HashSet<Vec<(Tetromino, Vec<XY>)>>,
+}
+
+impl<'a> NativeSolver<'a> {
+  fn search(
+    &mut self,
+    field: &Field,
+    rows: &[i32],
+    hand: &Hand,
+    held: bool,
+    operations: &mut Vec<SfinderOperation>,
+  ) {
+    if self.solutions.len() >= MAX_SOLUTIONS || self.control.is_halted() {
+      return;
+    }
+
+    let height = self.request.clear_line - (MAX_FIELD_HEIGHT - rows.len() as i32);
+    if height <= 0 {
+      self.add_solution(operations);
+      return;
+    }
+
+    let empty_cells = (0..height)
+      .flat_map(|y| (0..MAX_FIELD_WIDTH).map(move |x| (x, y)))
+      .filter(|&(x, y)| field.get_cell(x, y) == FieldCellValue::None)
+      .count();
+    let pieces = hand.current.iter().count()
+      + hand.hold.iter().count()
+      + self.queue.len().saturating_sub(hand.next);
+    if empty_cells % 4 != 0 || empty_cells > pieces * 4 {
+      return;
+    }
+
+    let current = match hand.current {
+      Some(x) => x,
+      None if hand.hold.is_some() && !held => {
+        self.search(field, rows, &hand.held(self.queue), true, operations);
+        return;
+      }
+      None => return,
+    };
+
+    for piece in placements(field, current, height) {
+      let route_searcher = RouteSearcher { field };
+      let route = route_searcher.search_route_with_drop_type(
+        spawn(current, field),
+        &piece,
+        self.request.drop_type,
+      );
+      if route.is_none() {
+        continue;
+      }
+
+      let op = match to_operation(&piece, rows) {
+        Some(x) => x,
+        None => continue,
+      };
+
+      let mut next_field = field.clone();
+      for (x, y) in piece.blocks() {
+        next_field.set_cell(x, y, FieldCellValue::from(current));
+      }
+      let (next_field, next_rows) = clear_lines(&next_field, rows);
+
+      operations.push(op);
+      self.search(
+        &next_field,
+        &next_rows,
+        &hand.placed(self.queue),
+        false,
+        operations,
+      );
+      operations.pop();
+    }
+
+    if self.request.use_hold && !held {
+      self.search(field, rows, &hand.held(self.queue), true, operations);
+    }
+  }
+
+  fn add_solution(&mut self, operations: &[SfinderOperation]) {
+    let mut field = self.request.field.clone();
+    for op in operations {
+      for (x, y) in op.blocks() {
+        field.set_cell(x, y, FieldCellValue::from(op.r#type));
+      }
+    }
+
+    let fumen = TetfuEncoder::new().encode(&Tetsimu2Content {
+      field: field.clone(),
+      comment: String::from(""),
+    });
+
+    let mut solution = SfinderSolution {
+      fumen,
+      used: operations.iter().map(|op| op.r#type).collect(),
+      operations: operations.to_vec(),
+      field,
+      deletes_line: false,
+    };
+
+    if self.keys.insert(placement_key(&solution)) {
+      solution.deletes_line = deletes_line_before_last(&solution);
+      self.solutions.push(solution);
+    }
+  }
+}
+
+fn to_operation(piece: &CurrentTetromino, rows: &[i32]) -> Option<SfinderOperation> {
+  let blocks = piece.blocks();
+  let targets: Vec<XY> = blocks.iter().map(|&(x, y)| (x, rows[y as usize])).collect();
+
+  let (min_y, max_y) = blocks
+    .iter()
+    .fold((i32::MAX, i32::MIN), |(min, max), &(_, y)| {
+      (min.min(y), max.max(y))
+    });
+  if rows[max_y as usize] - rows[min_y as usize] != max_y - min_y {
+    return None;
+  }
+
+  Placement::from_blocks(
+    CoordinateSystem::Sfinder,
+    piece.r#type,
+    piece.direction,
+    &targets,
+  )
+  .map(|x| SfinderOperation::from_placement(&x))
+}
+
+fn clear_lines(field: &Field, rows: &[i32]) -> (Field, Vec<i32>) {
+  let mut cleared_field = Field::new();
+  let mut remaining_rows = vec![];
+  for y in 0..rows.len() as i32 {
+    if (0..MAX_FIELD_WIDTH).all(|x| field.get_cell(x, y) != FieldCellValue::None) {
+      continue;
+    }
+
+    let live_y = remaining_rows.len() as i32;
+    for x in 0..MAX_FIELD_WIDTH {
+      cleared_field.set_cell(x, live_y, field.get_cell(x, y));
+    }
+    remaining_rows.push(rows[y as usize]);
+  }
+
+  (cleared_field, remaining_rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::DropType;
+  use std::convert::TryFrom;
+  use std::sync::atomic::AtomicBool;
+
+  fn solve(rows: &[&str], nexts: &str, use_hold: bool) -> Result<PcSolverOutput, String> {
+    let request = PcSolverRequest {
+      field: make_field(&rows.concat()),
+      nexts: String::from(nexts),
+      clear_line: rows.len() as i32,
+      use_hold,
+      drop_type: DropType::SoftDrop,
+    };
+    let is_halted = AtomicBool::new(false);
+    let control = SolveControl {
+      is_halted: &is_halted,
+      log: &|_| {},
+    };
+
+    NativeBackend.validate(&request)?;
+    NativeBackend.solve(&request, &control)
+  }
+
+  #[test]
+  fn solve_single_piece() {
+    #[rustfmt::skip]
+    let rows = [
+      "NNNNGGGGGG",
+    ];
+    let output = solve(&rows, "I", false).unwrap();
+    assert_eq!(output.unique.len(), 1);
+    assert_eq!(output.minimal.len(), 1);
+    assert_eq!(
+      output.unique[0].operations,
+      vec![SfinderOperation::try_from("I-Spawn-1-0").unwrap()]
+    );
+    assert_eq!(
+      output.message,
+      "Found path [unique] = 1\nFound path [minimal] = 1"
+    );
+  }
+
+  #[test]
+  fn solve_with_line_clear() {
+    #[rustfmt::skip]
+    let rows = [
+      "NNGGGGGGGG",
+      "NNGGGGGGGG",
+      "NNNNGGGGGG",
+    ];
+    let output = solve(&rows, "IO", false).unwrap();
+    assert_eq!(output.unique.len(), 1);
+    assert_eq!(output.unique[0].settles(), "IO");
+    assert_eq!(output.unique[0].deletes_line, true);
+    assert_eq!(
+      output.unique[0].operations[1],
+      SfinderOperation::try_from("O-Spawn-0-1").unwrap()
+    );
+
+    let output = solve(&rows, "OI", false).unwrap();
+    assert_eq!(output.unique.len(), 0);
+
+    let output = solve(&rows, "OI", true).unwrap();
+    assert_eq!(output.unique.len(), 1);
+  }
+
+  #[test]
+  fn validate_requires_fixed_queue() {
+    assert_eq!(
+      solve(&["NNNNGGGGGG"], "*p7", false),
+      Err(String::from(
+        "Native solver supports only a fixed sequence of pieces."
+      ))
+    );
+  }
+}
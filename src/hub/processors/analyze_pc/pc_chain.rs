@@ -0,0 +1,407 @@
+use crate::hub::messages::hub::analyze_pc::AnalyzePcMessageResBodyChain;
+use crate::hub::messages::tetsimu2::analyze_pc::ChainOptions;
+use crate::hub::processors::analyze_pc::post_process::fixed_queue;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverBackend;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverRequest;
+use crate::hub::processors::analyze_pc::solver_backend::SolveControl;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::field::Field;
+use std::cell::Cell;
+
+const MAX_CHAIN_DEPTH: u8 = 3;
+const MAX_CHAIN_BRANCHES: usize = 10;
+// Each solve is a separate solver run with its own timeout, so the whole tree shares this many.
+const MAX_CHAIN_SOLVES: usize = 20;
+const PC_CLEAR_LINE: i32 = 4;
+const PC_PIECES: usize = 10;
+const BAG: [Tetromino; 7] = [
+  Tetromino::I,
+  Tetromino::J,
+  Tetromino::L,
+  Tetromino::O,
+  Tetromino::S,
+  Tetromino::T,
+  Tetromino::Z,
+];
+
+// Pieces still to come: `known` in order (a carried hold comes first, since it can be played at
+// any time), followed by `left` pieces of `pool` in any order, followed by fresh bags. `pool` can
+// be larger than `left` when pieces drawn before the queue (the bag offset) are unknown.
+// `approximate` is set once a held piece had to be guessed from such a pool.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChainState {
+  pub known: Vec<Tetromino>,
+  pub pool: Vec<Tetromino>,
+  pub left: usize,
+  pub approximate: bool,
+}
+
+impl ChainState {
+  pub fn new(queue: &[Tetromino], bag_offset: usize) -> Self {
+    let drawn_in_bag = (bag_offset + queue.len()) % BAG.len();
+    let mut pool = vec![];
+    let mut left = 0;
+    if drawn_in_bag > 0 {
+      left = BAG.len() - drawn_in_bag;
+      pool = BAG.to_vec();
+      for t in &queue[queue.len().saturating_sub(drawn_in_bag)..] {
+        if let Some(i) = pool.iter().position(|x| x == t) {
+          pool.remove(i);
+        }
+      }
+    }
+
+    ChainState {
+      known: queue.to_vec(),
+      pool,
+      left,
+      approximate: false,
+    }
+  }
+
+  // `used` is sfinder's set of used pieces, not their order. Known pieces are always drawn first
+  // and a bag is drawn up before the next one, so pieces of the bag that are left over when the
+  // next bag is needed must have been held.
+  pub fn after(&self, used: &[Tetromino]) -> Self {
+    let mut known = self.known.clone();
+    let mut pool = self.pool.clone();
+    let mut left = self.left;
+    let mut approximate = self.approximate;
+
+    let mut rest = vec![];
+    for t in used {
+      match known.iter().position(|x| x == t) {
+        Some(i) => {
+          known.remove(i);
+        }
+        None => rest.push(*t),
+      }
+    }
+
+    while !rest.is_empty() {
+      let mut overflow = vec![];
+      for t in rest {
+        match pool.iter().position(|x| *x == t) {
+          Some(i) if left > 0 => {
+            pool.remove(i);
+            left -= 1;
+          }
+          _ => overflow.push(t),
+        }
+      }
+
+      if !overflow.is_empty() {
+        if left > 0 {
+          if pool.len() == left {
+            known.splice(0..0, pool.iter().copied());
+          } else {
+            approximate = true;
+          }
+        }
+        pool = BAG.to_vec();
+        left = BAG.len();
+      }
+      rest = overflow;
+    }
+    if left == 0 {
+      pool.clear();
+    }
+
+    ChainState {
+      known,
+      pool,
+      left,
+      approximate,
+    }
+  }
+
+  pub fn patterns(&self, pieces: usize) -> String {
+    let mut parts = vec![];
+    let known: String = self
+      .known
+      .iter()
+      .take(pieces)
+      .map(|t| format!("{:?}", t))
+      .collect();
+    if !known.is_empty() {
+      parts.push(known);
+    }
+
+    let mut rest = pieces - self.known.len().min(pieces);
+    if rest > 0 && self.left > 0 {
+      let n = rest.min(self.left);
+      let pool: String = self.pool.iter().map(|t| format!("{:?}", t)).collect();
+      parts.push(format!("[{}]p{}", pool, n));
+      rest -= n;
+    }
+
+    while rest > 0 {
+      let n = rest.min(BAG.len());
+      parts.push(format!("*p{}", n));
+      rest -= n;
+    }
+
+    parts.join(",")
+  }
+}
+
+struct ChainAnalyzer<'a> {
+  request: &'a PcSolverRequest,
+  options: &'a ChainOptions,
+  backend: &'a dyn PcSolverBackend,
+  control: &'a SolveControl<'a>,
+  solves_left: Cell<usize>,
+}
+
+pub fn analyze_chains(
+  solutions: &[SfinderSolution],
+  request: &PcSolverRequest,
+  options: &ChainOptions,
+  backend: &dyn PcSolverBackend,
+  control: &SolveControl<'_>,
+) -> Result<Vec<AnalyzePcMessageResBodyChain>, String> {
+  let queue = match fixed_queue(&request.nexts) {
+    Some(x) => x,
+    None => {
+      return Err(String::from(
+        "Chain analysis requires a fixed sequence of pieces.",
+      ))
+    }
+  };
+
+  let analyzer = ChainAnalyzer {
+    request,
+    options,
+    backend,
+    control,
+    solves_left: Cell::new(MAX_CHAIN_SOLVES),
+  };
+  let state = ChainState::new(&queue, options.bag_offset);
+  let depth = options.depth.clamp(1, MAX_CHAIN_DEPTH);
+
+  solutions
+    .iter()
+    .take(analyzer.max_branches())
+    .map(|solution| analyzer.analyze(solution, &state, depth))
+    .collect()
+}
+
+impl<'a> ChainAnalyzer<'a> {
+  fn max_branches(&self) -> usize {
+    self.options.max_branches.clamp(1, MAX_CHAIN_BRANCHES)
+  }
+
+  fn analyze(
+    &self,
+    solution: &SfinderSolution,
+    parent: &ChainState,
+    depth: u8,
+  ) -> Result<AnalyzePcMessageResBodyChain, String> {
+    let state = parent.after(&solution.used);
+    let mut chain = AnalyzePcMessageResBodyChain {
+      settles: solution.settles(),
+      fumen: solution.fumen.clone(),
+      next_patterns: String::new(),
+      solution_count: 0,
+      success_count: 0,
+      message: String::new(),
+      approximate: state.approximate,
+      truncated: false,
+      children: vec![],
+    };
+
+    if depth == 0 {
+      chain.success_count = 1;
+      return Ok(chain);
+    }
+
+    if self.control.is_halted() {
+      return Err(String::from("Analysis was canceled."));
+    }
+
+    if self.solves_left.get() == 0 {
+      chain.truncated = true;
+      chain.message = format!(
+        "Chain analysis stopped after {} solver runs.",
+        MAX_CHAIN_SOLVES
+      );
+      return Ok(chain);
+    }
+    self.solves_left.set(self.solves_left.get() - 1);
+
+    let pieces = PC_PIECES + if self.request.use_hold { 1 } else { 0 };
+    chain.next_patterns = state.patterns(pieces);
+    let next_request = PcSolverRequest {
+      field: Field::new(),
+      nexts: chain.next_patterns.clone(),
+      clear_line: PC_CLEAR_LINE,
+      use_hold: self.request.use_hold,
+      drop_type: self.request.drop_type,
+    };
+
+    self.control.log(&format!(
+      "Analyzing next PC with {}...",
+      chain.next_patterns
+    ));
+    let output = self
+      .backend
+      .validate(&next_request)
+      .and_then(|_| self.backend.solve(&next_request, self.control));
+
+    match output {
+      Ok(output) => {
+        chain.solution_count = output.unique.len();
+        for next in output.unique.iter().take(self.max_branches()) {
+          let child = self.analyze(next, &state, depth - 1)?;
+          chain.success_count += child.success_count;
+          chain.truncated |= child.truncated;
+          chain.children.push(child);
+        }
+      }
+      Err(e) => {
+        if self.control.is_halted() {
+          return Err(e);
+        }
+        chain.message = e;
+      }
+    }
+
+    Ok(chain)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::hub::processors::analyze_pc::fake_backend::FakeBackend;
+  use crate::tetsimu2::core::DropType;
+  use std::sync::atomic::AtomicBool;
+
+  fn queue(s: &str) -> Vec<Tetromino> {
+    fixed_queue(s).unwrap()
+  }
+
+  #[test]
+  fn new_state_should_find_rest_of_bag() {
+    let state = ChainState::new(&queue("IJLOSTZIJL"), 0);
+    assert_eq!(state.pool, queue("OSTZ"));
+
+    let state = ChainState::new(&queue("IJLOSTZ"), 0);
+    assert_eq!(state.pool, vec![]);
+
+    let state = ChainState::new(&queue("TZ"), 3);
+    assert_eq!(state.pool, queue("IJLOS"));
+    assert_eq!(state.left, 2);
+    assert_eq!(state.patterns(11), "TZ,[IJLOS]p2,*p7");
+  }
+
+  #[test]
+  fn after_should_carry_unused_pieces() {
+    let state = ChainState::new(&queue("IJLOSTZIJLO"), 0);
+    let state = state.after(&queue("IJLOSTZIJL"));
+    assert_eq!(state.known, queue("O"));
+    assert_eq!(state.pool, queue("STZ"));
+    assert_eq!(state.left, 3);
+
+    let state = state.after(&queue("OSTZIJ"));
+    assert_eq!(state.known, vec![]);
+    assert_eq!(state.pool, queue("LOSTZ"));
+    assert_eq!(state.left, 5);
+
+    // The pieces drawn before the queue are gone once the rest of the bag is used.
+    let state = ChainState::new(&queue("TZ"), 3).after(&queue("TZIJ"));
+    assert_eq!(state.pool, vec![]);
+    assert_eq!(state.left, 0);
+    assert_eq!(state.patterns(3), "*p3");
+  }
+
+  #[test]
+  fn after_should_not_depend_on_order_of_used_pieces() {
+    let state = ChainState {
+      known: vec![],
+      pool: queue("OS"),
+      left: 2,
+      approximate: false,
+    };
+
+    // T comes from the next bag, so S was drawn before it and held.
+    let expected = ChainState {
+      known: queue("S"),
+      pool: queue("IJLOSZ"),
+      left: 6,
+      approximate: false,
+    };
+    assert_eq!(state.after(&queue("TO")), expected);
+    assert_eq!(state.after(&queue("OT")), expected);
+  }
+
+  #[test]
+  fn after_should_be_approximate_when_held_piece_is_unknown() {
+    // One of IJLOS is left in the bag when the second T comes, but which one is unknown.
+    let state = ChainState::new(&queue("TZ"), 4).after(&queue("TZT"));
+    assert_eq!(state.known, vec![]);
+    assert_eq!(state.pool, queue("IJLOSZ"));
+    assert_eq!(state.left, 6);
+    assert_eq!(state.approximate, true);
+  }
+
+  #[test]
+  fn patterns() {
+    let state = ChainState {
+      known: queue("O"),
+      pool: queue("STZ"),
+      left: 3,
+      approximate: false,
+    };
+    assert_eq!(state.patterns(11), "O,[STZ]p3,*p7");
+    assert_eq!(state.patterns(2), "O,[STZ]p1");
+
+    let state = ChainState {
+      known: vec![],
+      pool: vec![],
+      left: 0,
+      approximate: false,
+    };
+    assert_eq!(state.patterns(10), "*p7,*p3");
+  }
+
+  #[test]
+  fn analyze_should_stop_at_solve_budget() {
+    let backend = FakeBackend::new("solved");
+    let is_halted = AtomicBool::new(false);
+    let log = |_: &str| {};
+    let control = SolveControl {
+      is_halted: &is_halted,
+      log: &log,
+    };
+    let request = PcSolverRequest {
+      field: Field::new(),
+      nexts: String::from("I"),
+      clear_line: 1,
+      use_hold: true,
+      drop_type: DropType::SoftDrop,
+    };
+    let solution = backend.solve(&request, &control).unwrap().unique.remove(0);
+
+    let options = ChainOptions {
+      depth: 2,
+      ..ChainOptions::default()
+    };
+    let analyzer = ChainAnalyzer {
+      request: &request,
+      options: &options,
+      backend: &backend,
+      control: &control,
+      solves_left: Cell::new(1),
+    };
+    let chain = analyzer
+      .analyze(&solution, &ChainState::new(&queue("I"), 0), 2)
+      .unwrap();
+
+    assert_eq!(chain.truncated, true);
+    assert_eq!(chain.children[0].truncated, true);
+    assert_eq!(chain.children[0].children.len(), 0);
+    assert_eq!(backend.requests.lock().unwrap().len(), 2);
+  }
+}
@@ -0,0 +1,317 @@
+use crate::hub::messages::tetsimu2::analyze_pc::PostProcessOptions;
+use crate::hub::messages::tetsimu2::analyze_pc::SolutionSortKey;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderOperation;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::processors::analyze_pc::solution_steps::PlacingState;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::XY;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct SolutionTraits {
+  // Unknown without a fixed queue, since sfinder's `use` column is not the order pieces come in.
+  needs_hold: Option<bool>,
+  tspin: bool,
+}
+
+pub fn fixed_queue(nexts: &str) -> Option<Vec<Tetromino>> {
+  nexts
+    .trim()
+    .chars()
+    .map(|c| Tetromino::try_from(c).ok())
+    .collect()
+}
+
+pub fn post_process(
+  solutions: Vec<SfinderSolution>,
+  queue: Option<&[Tetromino]>,
+  options: &PostProcessOptions,
+) -> Vec<SfinderSolution> {
+  if *options == PostProcessOptions::default() {
+    return solutions;
+  }
+
+  let mut keys = HashSet::new();
+  let mut solutions: Vec<_> = solutions
+    .into_iter()
+    .filter(|solution| !options.without_line_deletion || !solution.deletes_line)
+    .filter(|solution| !options.dedupe_mirrors || keys.insert(placement_key(solution)))
+    .map(|solution| {
+      let traits = solution_traits(&solution, queue);
+      (solution, traits)
+    })
+    .filter(|(_, traits)| !options.without_hold || traits.needs_hold != Some(true))
+    .filter(|(_, traits)| !options.tspin_only || traits.tspin)
+    .collect();
+
+  solutions.sort_by(|(solution1, traits1), (solution2, traits2)| {
+    options
+      .sort_by
+      .iter()
+      .map(|key| match key {
+        SolutionSortKey::TSpin => traits2.tspin.cmp(&traits1.tspin),
+        SolutionSortKey::WithoutHold => traits1.needs_hold.cmp(&traits2.needs_hold),
+        SolutionSortKey::WithoutLineDeletion => solution1.deletes_line.cmp(&solution2.deletes_line),
+        SolutionSortKey::FewerPieces => solution1.operations.len().cmp(&solution2.operations.len()),
+      })
+      .find(|ordering| *ordering != Ordering::Equal)
+      .unwrap_or(Ordering::Equal)
+  });
+
+  let limit = options.limit.unwrap_or(solutions.len());
+  solutions
+    .into_iter()
+    .take(limit)
+    .map(|(solution, _)| solution)
+    .collect()
+}
+
+// Placements which differ only in rotation state (e.g. S spawn/reverse) occupy the same cells.
+pub fn placement_key(solution: &SfinderSolution) -> Vec<(Tetromino, Vec<XY>)> {
+  let mut key: Vec<_> = solution
+    .operations
+    .iter()
+    .map(|op| {
+      let mut blocks = op.blocks();
+      blocks.sort();
+      (op.r#type, blocks)
+    })
+    .collect();
+  key.sort();
+  key
+}
+
+fn solution_traits(solution: &SfinderSolution, queue: Option<&[Tetromino]>) -> SolutionTraits {
+  let order = queue.and_then(|queue| find_order(solution, Some(queue)));
+  let needs_hold = queue.map(|_| order.is_none());
+  let order = order.or_else(|| find_order(solution, None));
+
+  let tspin = match order {
+    Some(order) => is_tspin_solution(solution, &order),
+    None => false,
+  };
+
+  SolutionTraits { needs_hold, tspin }
+}
+
+fn find_order(solution: &SfinderSolution, queue: Option<&[Tetromino]>) -> Option<Vec<usize>> {
+  if let Some(queue) = queue {
+    if queue.len() < solution.operations.len() {
+      return None;
+    }
+  }
+
+  let mut state = PlacingState::new(solution);
+  let mut order = vec![];
+
+  if search_order(&solution.operations, queue, &mut state, &mut order) {
+    Some(order)
+  } else {
+    None
+  }
+}
+
+fn search_order(
+  operations: &[SfinderOperation],
+  queue: Option<&[Tetromino]>,
+  state: &mut PlacingState,
+  order: &mut Vec<usize>,
+) -> bool {
+  if order.len() == operations.len() {
+    return true;
+  }
+
+  let next_type = queue.map(|queue| queue[order.len()]);
+  for (i, op) in operations.iter().enumerate() {
+    if state.used[i] || next_type.map_or(false, |t| t != op.r#type) {
+      continue;
+    }
+
+    if !state.is_supported(op) {
+      continue;
+    }
+
+    let prev_field = state.field.clone();
+    let prev_cleared = state.cleared;
+    state.place(op);
+    state.used[i] = true;
+    order.push(i);
+
+    if search_order(operations, queue, state, order) {
+      return true;
+    }
+
+    order.pop();
+    state.used[i] = false;
+    state.field = prev_field;
+    state.cleared = prev_cleared;
+  }
+
+  false
+}
+
+fn is_tspin_solution(solution: &SfinderSolution, order: &[usize]) -> bool {
+  let mut state = PlacingState::new(solution);
+
+  for &i in order {
+    let op = &solution.operations[i];
+    let filled_corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+      .iter()
+      .filter(|(dx, dy)| state.field.get_cell(op.x + dx, op.y + dy) != FieldCellValue::None)
+      .count();
+
+    let cleared_lines = state.place(op);
+    if op.r#type == Tetromino::T && filled_corners >= 3 && cleared_lines > 0 {
+      return true;
+    }
+  }
+
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+
+  fn make_solution(rows: &[&str], operations: &[&str]) -> SfinderSolution {
+    let operations: Vec<_> = operations
+      .iter()
+      .map(|op| SfinderOperation::try_from(*op).unwrap())
+      .collect();
+
+    SfinderSolution {
+      fumen: String::new(),
+      used: operations.iter().map(|op| op.r#type).collect(),
+      operations,
+      field: make_field(&rows.concat()),
+      deletes_line: false,
+    }
+  }
+
+  fn stacked_solution() -> SfinderSolution {
+    #[rustfmt::skip]
+    let rows = [
+      "OOGGGGGGGG",
+      "OOGGGGGGGG",
+      "IIIIGGGGGG",
+    ];
+    make_solution(&rows, &["O-Spawn-0-1", "I-Spawn-1-0"])
+  }
+
+  fn tspin_solution() -> SfinderSolution {
+    #[rustfmt::skip]
+    let rows = [
+      "GGGGNNNNNN",
+      "GGGTTTGGGG",
+      "GGGGTGGGGG",
+    ];
+    make_solution(&rows, &["T-Reverse-4-1"])
+  }
+
+  #[test]
+  fn fixed_queue() {
+    assert_eq!(
+      super::fixed_queue("TIO"),
+      Some(vec![Tetromino::T, Tetromino::I, Tetromino::O])
+    );
+    assert_eq!(super::fixed_queue("*p7"), None);
+  }
+
+  #[test]
+  fn solution_traits_should_detect_hold() {
+    let solution = stacked_solution();
+    assert_eq!(
+      solution_traits(&solution, Some(&[Tetromino::I, Tetromino::O])),
+      SolutionTraits {
+        needs_hold: Some(false),
+        tspin: false,
+      }
+    );
+    assert_eq!(
+      solution_traits(&solution, Some(&[Tetromino::O, Tetromino::I])),
+      SolutionTraits {
+        needs_hold: Some(true),
+        tspin: false,
+      }
+    );
+    assert_eq!(
+      solution_traits(&solution, None),
+      SolutionTraits {
+        needs_hold: None,
+        tspin: false,
+      }
+    );
+  }
+
+  #[test]
+  fn solution_traits_should_detect_tspin() {
+    assert_eq!(
+      solution_traits(&tspin_solution(), None),
+      SolutionTraits {
+        needs_hold: None,
+        tspin: true,
+      }
+    );
+  }
+
+  #[test]
+  fn post_process_should_filter_and_sort() {
+    let solutions = vec![stacked_solution(), tspin_solution()];
+
+    let options = PostProcessOptions {
+      sort_by: vec![SolutionSortKey::TSpin],
+      ..Default::default()
+    };
+    let actual = post_process(solutions.clone(), None, &options);
+    assert_eq!(actual, vec![tspin_solution(), stacked_solution()]);
+
+    let options = PostProcessOptions {
+      without_hold: true,
+      ..Default::default()
+    };
+    let queue = [Tetromino::T, Tetromino::O, Tetromino::I];
+    let actual = post_process(solutions.clone(), Some(&queue), &options);
+    assert_eq!(actual, vec![tspin_solution()]);
+
+    // Hold cannot be told without a fixed queue, so nothing is filtered or reordered by it.
+    let actual = post_process(solutions.clone(), None, &options);
+    assert_eq!(actual, solutions);
+    let options = PostProcessOptions {
+      sort_by: vec![SolutionSortKey::WithoutHold],
+      ..Default::default()
+    };
+    let actual = post_process(vec![tspin_solution(), stacked_solution()], None, &options);
+    assert_eq!(actual, vec![tspin_solution(), stacked_solution()]);
+
+    let options = PostProcessOptions {
+      sort_by: vec![SolutionSortKey::FewerPieces],
+      limit: Some(1),
+      ..Default::default()
+    };
+    let actual = post_process(solutions, None, &options);
+    assert_eq!(actual, vec![tspin_solution()]);
+  }
+
+  #[test]
+  fn post_process_should_dedupe_mirrors() {
+    #[rustfmt::skip]
+    let rows = [
+      "IIIIGGGGGG",
+    ];
+    let solutions = vec![
+      make_solution(&rows, &["I-Spawn-1-0"]),
+      make_solution(&rows, &["I-Reverse-2-0"]),
+    ];
+
+    let options = PostProcessOptions {
+      dedupe_mirrors: true,
+      ..Default::default()
+    };
+    let actual = post_process(solutions, None, &options);
+    assert_eq!(actual, vec![make_solution(&rows, &["I-Spawn-1-0"])]);
+  }
+}
@@ -0,0 +1,371 @@
+use crate::hub::processors::analyze_pc::sfinder_csv;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::processors::analyze_pc::sfinder_csv::SolutionKind;
+use crate::hub::processors::analyze_pc::sfinder_features;
+use crate::hub::processors::analyze_pc::sfinder_features::SfinderFeatures;
+use crate::hub::processors::analyze_pc::sfinder_probe::sfinder_command;
+use crate::hub::processors::analyze_pc::sfinder_probe::MAIN_JAR;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverBackend;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverOutput;
+use crate::hub::processors::analyze_pc::solver_backend::PcSolverRequest;
+use crate::hub::processors::analyze_pc::solver_backend::SolveControl;
+use crate::hub::shared_context::SharedContext;
+use crate::settings::Settings;
+use crate::tetfu::core::Tetsimu2Content;
+use crate::tetfu::tetfu_encoder::TetfuEncoder;
+use crate::tetsimu2::core::DropType;
+use log::{debug, info, warn};
+use std::fs;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const WORK_DIR: &str = "tetsimu2_hub";
+
+pub struct SfinderBackend {
+  settings: Arc<Settings>,
+  context: Arc<SharedContext>,
+  child: Mutex<Option<Child>>,
+}
+
+struct WorkDir {
+  path: PathBuf,
+}
+
+impl WorkDir {
+  fn create() -> std::io::Result<Self> {
+    let path = std::env::temp_dir()
+      .join(WORK_DIR)
+      .join(Uuid::new_v4().to_string());
+    fs::create_dir_all(&path)?;
+    Ok(WorkDir { path })
+  }
+}
+
+impl Drop for WorkDir {
+  fn drop(&mut self) {
+    if let Err(e) = fs::remove_dir_all(&self.path) {
+      warn!("Failed to remove {:?}. {}", self.path, e);
+    }
+  }
+}
+
+struct SfinderOutput {
+  status: ExitStatus,
+  stdout: String,
+  stderr: String,
+}
+
+impl PcSolverBackend for SfinderBackend {
+  fn validate(&self, request: &PcSolverRequest) -> Result<(), String> {
+    let sf_root = match &self.settings.solution_finder.path {
+      Some(x) => x,
+      None => return Err(String::from("Solution finder settings is not set.")),
+    };
+
+    if !Path::new(sf_root).join(MAIN_JAR).exists() {
+      return Err(format!("Cannot find {}.", MAIN_JAR));
+    }
+
+    self
+      .sfinder_features()
+      .validate_drop_type(request.drop_type)
+  }
+
+  fn fingerprint(&self) -> Option<String> {
+    let cache = self.context.analysis_cache.as_ref()?;
+    let sf_root = self.settings.solution_finder.path.as_ref()?;
+    match cache.jar_fingerprint(&Path::new(sf_root).join(MAIN_JAR)) {
+      Ok(x) => Some(x),
+      Err(e) => {
+        warn!("Could not fingerprint {}. {}", MAIN_JAR, e);
+        None
+      }
+    }
+  }
+
+  fn solve(
+    &self,
+    request: &PcSolverRequest,
+    control: &SolveControl<'_>,
+  ) -> Result<PcSolverOutput, String> {
+    let work_dir =
+      WorkDir::create().map_err(|e| format!("Failed to create working directory. {}", e))?;
+    debug!("work_dir: {:?}", work_dir.path);
+
+    let tetfu_encoder = TetfuEncoder::new();
+    let tetfu = tetfu_encoder.encode(&Tetsimu2Content {
+      field: request.field.clone(),
+      comment: String::from(""),
+    });
+
+    let use_hold = if request.use_hold { "use" } else { "avoid" };
+
+    let mut command = sfinder_command(&self.settings.solution_finder);
+    command
+      .arg("path")
+      .arg("--tetfu")
+      .arg(tetfu)
+      .arg("--patterns")
+      .arg(&request.nexts)
+      .arg("--clear-line")
+      .arg(request.clear_line.to_string())
+      .arg("--hold")
+      .arg(use_hold)
+      .arg("--drop")
+      .arg(sfinder_features::drop_option(request.drop_type))
+      .arg("--format")
+      .arg("csv")
+      .arg("--output-base")
+      .arg(work_dir.path.join("path.txt"))
+      .arg("--log-path")
+      .arg(work_dir.path.join("last_output.txt"))
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped());
+
+    if request.drop_type == DropType::OneHundredEighty {
+      command.arg("--kicks").arg(sfinder_features::KICKS_180);
+    }
+
+    let child = command.spawn().map_err(|e| e.to_string())?;
+    let output = self.wait_sfinder(child, control)?;
+    debug!("status: {}", output.status);
+
+    if !output.status.success() {
+      warn!("stderr:\n{}", output.stderr);
+      return Err(error_message(&output.stderr));
+    }
+
+    debug!("stdout:\n{}", &output.stdout);
+    read_output(&work_dir.path, &output.stdout)
+  }
+
+  fn halt(&self) {
+    self.kill_sfinder();
+  }
+}
+
+impl SfinderBackend {
+  pub fn new(settings: &Arc<Settings>, context: &Arc<SharedContext>) -> Self {
+    SfinderBackend {
+      settings: settings.clone(),
+      context: context.clone(),
+      child: Mutex::new(None),
+    }
+  }
+
+  fn sfinder_features(&self) -> SfinderFeatures {
+    let mut features = self.context.sfinder_features.lock().unwrap();
+    if let Some(x) = features.as_ref() {
+      return x.clone();
+    }
+
+    let output = sfinder_command(&self.settings.solution_finder)
+      .arg("path")
+      .arg("-h")
+      .output();
+
+    match output {
+      Ok(output) => {
+        let help = format!(
+          "{}\n{}",
+          String::from_utf8_lossy(&output.stdout),
+          String::from_utf8_lossy(&output.stderr)
+        );
+        let probed = SfinderFeatures::from_help(&help);
+        debug!("sfinder features: {:?}", probed);
+        *features = Some(probed.clone());
+        probed
+      }
+      Err(e) => {
+        warn!("Could not probe solution finder features. {}", e);
+        SfinderFeatures::default()
+      }
+    }
+  }
+
+  fn wait_sfinder(
+    &self,
+    mut child: Child,
+    control: &SolveControl<'_>,
+  ) -> Result<SfinderOutput, String> {
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    *self.child.lock().unwrap() = Some(child);
+
+    let (log_s, log_r) = mpsc::channel();
+    let stdout_reader = thread::spawn(move || {
+      let mut lines = vec![];
+      for line in BufReader::new(stdout).lines() {
+        let line = match line {
+          Ok(line) => line,
+          Err(_) => break,
+        };
+
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+          log_s.send(String::from(trimmed)).ok();
+        }
+        lines.push(line);
+      }
+      lines.join("\n")
+    });
+
+    let stderr_reader = thread::spawn(move || {
+      let mut contents = String::new();
+      BufReader::new(stderr).read_to_string(&mut contents).ok();
+      contents
+    });
+
+    let timeout = self
+      .settings
+      .solution_finder
+      .timeout_sec
+      .map(Duration::from_secs);
+    let started_at = Instant::now();
+
+    let wait_result = loop {
+      for line in log_r.try_iter() {
+        control.log(&line);
+      }
+
+      if control.is_halted() {
+        self.kill_sfinder();
+        break Err(String::from("Analysis was canceled."));
+      }
+
+      if let Some(timeout) = timeout {
+        if started_at.elapsed() >= timeout {
+          self.kill_sfinder();
+          break Err(format!(
+            "Analysis timed out after {} seconds.",
+            timeout.as_secs()
+          ));
+        }
+      }
+
+      let status = match self.child.lock().unwrap().as_mut() {
+        Some(child) => child.try_wait(),
+        None => break Err(String::from("Analysis was canceled.")),
+      };
+
+      match status {
+        Ok(Some(status)) => break Ok(status),
+        Ok(None) => thread::sleep(Duration::from_millis(100)),
+        Err(e) => {
+          self.kill_sfinder();
+          break Err(e.to_string());
+        }
+      }
+    };
+
+    self.child.lock().unwrap().take();
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+    for line in log_r.try_iter() {
+      control.log(&line);
+    }
+
+    wait_result.map(|status| SfinderOutput {
+      status,
+      stdout,
+      stderr,
+    })
+  }
+
+  fn kill_sfinder(&self) {
+    if let Some(child) = self.child.lock().unwrap().as_mut() {
+      info!("Kill solution finder.");
+      if let Err(e) = child.kill() {
+        warn!("{}", e);
+      }
+      child.wait().ok();
+    }
+  }
+}
+
+pub fn read_output(output_dir: &Path, stdout: &str) -> Result<PcSolverOutput, String> {
+  Ok(PcSolverOutput {
+    message: analyze_path_nums(stdout),
+    minimal: read_csv(output_dir, SolutionKind::Minimal)?,
+    unique: read_csv(output_dir, SolutionKind::Unique)?,
+  })
+}
+
+pub fn error_message(stderr: &str) -> String {
+  stderr
+    .lines()
+    .map(|s| s.trim())
+    .find(|line| line.starts_with("Message: "))
+    .map(|line| String::from(&line["Message: ".len()..]))
+    .unwrap_or_else(|| String::from("Failed to analyze."))
+}
+
+fn read_csv(output_dir: &Path, kind: SolutionKind) -> Result<Vec<SfinderSolution>, String> {
+  let path = output_dir.join(kind.file_name());
+  let contents = match fs::read_to_string(&path) {
+    Ok(x) => x,
+    Err(_) => {
+      return Err(format!(
+        "File({}) cannot be opened.",
+        path.into_os_string().into_string().unwrap()
+      ))
+    }
+  };
+
+  sfinder_csv::parse(&contents).map_err(|e| format!("Failed to read {}. {}", kind.file_name(), e))
+}
+
+fn analyze_path_nums(stdout: &str) -> String {
+  let mut found_paths = vec![];
+  let lines = stdout.split('\n').map(|s| s.trim());
+  for line in lines {
+    if line.starts_with("Found path") {
+      found_paths.push(line);
+    }
+  }
+
+  found_paths.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  #[test]
+  fn error_message() {
+    let stderr = r#"
+Exception in thread "main" entry.path.PathException
+  Message: Should specify clear-line < field-height
+"#;
+    assert_eq!(
+      super::error_message(stderr),
+      "Should specify clear-line < field-height"
+    );
+    assert_eq!(
+      super::error_message("java.lang.OutOfMemoryError"),
+      "Failed to analyze."
+    );
+  }
+
+  #[test]
+  fn analyze_path_nums() {
+    let stdout = r#"
+# Output
+Found path [unique] = 3
+Found path [minimal] = 1
+done
+"#;
+    assert_eq!(
+      super::analyze_path_nums(stdout),
+      "Found path [unique] = 3\nFound path [minimal] = 1"
+    );
+  }
+}
@@ -0,0 +1,541 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::tetfu::tetfu_decoder::TetfuDecoder;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::core::XY;
+use crate::tetsimu2::field::Field;
+use std::convert::TryFrom;
+
+// sfinder writes one solution per row. Columns are looked up by header name:
+//   fumen      : tetfu of the solved field
+//   use        : used pieces (e.g. "TILJ")
+//   operations : placements joined by ';' (e.g. "T-Spawn-4-1;I-Left-0-1"). Optional, without it
+//                placements are read back from the piece colours in the fumen.
+const FUMEN_COLUMN: &str = "fumen";
+const USE_COLUMN: &str = "use";
+const OPERATIONS_COLUMN: &str = "operations";
+
+const TYPES: [Tetromino; 7] = [
+  Tetromino::I,
+  Tetromino::J,
+  Tetromino::L,
+  Tetromino::O,
+  Tetromino::S,
+  Tetromino::T,
+  Tetromino::Z,
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SolutionKind {
+  Minimal,
+  Unique,
+}
+
+impl SolutionKind {
+  pub fn file_name(&self) -> &'static str {
+    match self {
+      SolutionKind::Minimal => "path_minimal.csv",
+      SolutionKind::Unique => "path_unique.csv",
+    }
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SfinderRotation {
+  Spawn,
+  Right,
+  Reverse,
+  Left,
+}
+
+impl TryFrom<&str> for SfinderRotation {
+  type Error = String;
+
+  fn try_from(s: &str) -> Result<Self, Self::Error> {
+    let rotation = match s {
+      "Spawn" => SfinderRotation::Spawn,
+      "Right" => SfinderRotation::Right,
+      "Reverse" => SfinderRotation::Reverse,
+      "Left" => SfinderRotation::Left,
+      _ => return Err(format!("Cannot convert from '{}' to rotation", s)),
+    };
+
+    Ok(rotation)
+  }
+}
+
+impl From<SfinderRotation> for Direction {
+  fn from(rotation: SfinderRotation) -> Self {
+    match rotation {
+      SfinderRotation::Spawn => Direction::Up,
+      SfinderRotation::Right => Direction::Right,
+      SfinderRotation::Reverse => Direction::Down,
+      SfinderRotation::Left => Direction::Left,
+    }
+  }
+}
+
+impl From<Direction> for SfinderRotation {
+  fn from(direction: Direction) -> Self {
+    match direction {
+      Direction::Up => SfinderRotation::Spawn,
+      Direction::Right => SfinderRotation::Right,
+      Direction::Down => SfinderRotation::Reverse,
+      Direction::Left => SfinderRotation::Left,
+    }
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SfinderOperation {
+  pub r#type: Tetromino,
+  pub rotation: SfinderRotation,
+  pub x: i32,
+  pub y: i32,
+}
+
+impl SfinderOperation {
+  pub fn blocks(&self) -> Vec<XY> {
+    self.placement().blocks(CoordinateSystem::Sfinder)
+  }
+
+  pub fn placement(&self) -> Placement {
+    Placement {
+      r#type: self.r#type,
+      direction: Direction::from(self.rotation),
+      x: self.x,
+      y: self.y,
+    }
+  }
+
+  pub fn from_placement(placement: &Placement) -> Self {
+    SfinderOperation {
+      r#type: placement.r#type,
+      rotation: SfinderRotation::from(placement.direction),
+      x: placement.x,
+      y: placement.y,
+    }
+  }
+}
+
+impl TryFrom<&str> for SfinderOperation {
+  type Error = String;
+
+  fn try_from(s: &str) -> Result<Self, Self::Error> {
+    let values: Vec<&str> = s.trim().split('-').collect();
+    if values.len() != 4 {
+      return Err(format!("Invalid operation '{}'", s));
+    }
+
+    let mut type_chars = values[0].chars();
+    let r#type = match (type_chars.next(), type_chars.next()) {
+      (Some(c), None) => Tetromino::try_from(c)?,
+      _ => return Err(format!("Invalid operation '{}'", s)),
+    };
+    let rotation = SfinderRotation::try_from(values[1])?;
+    let x = values[2]
+      .parse()
+      .map_err(|_| format!("Invalid operation '{}'", s))?;
+    let y = values[3]
+      .parse()
+      .map_err(|_| format!("Invalid operation '{}'", s))?;
+
+    Ok(SfinderOperation {
+      r#type,
+      rotation,
+      x,
+      y,
+    })
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SfinderSolution {
+  pub fumen: String,
+  pub used: Vec<Tetromino>,
+  pub operations: Vec<SfinderOperation>,
+  pub field: Field,
+  pub deletes_line: bool,
+}
+
+impl SfinderSolution {
+  pub fn initial_field(&self) -> Field {
+    let mut field = self.field.clone();
+    for op in &self.operations {
+      for (x, y) in op.blocks() {
+        field.set_cell(x, y, FieldCellValue::None);
+      }
+    }
+
+    field
+  }
+
+  pub fn settles(&self) -> String {
+    let types: Vec<Tetromino> = if self.operations.is_empty() {
+      self.used.clone()
+    } else {
+      self.operations.iter().map(|op| op.r#type).collect()
+    };
+
+    types.iter().map(|t| format!("{:?}", t)).collect()
+  }
+}
+
+pub fn parse(content: &str) -> Result<Vec<SfinderSolution>, String> {
+  let mut lines = content
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty());
+
+  let header = match lines.next() {
+    Some((_, line)) => split_row(line),
+    None => return Ok(vec![]),
+  };
+
+  let column = |name: &str| -> Result<usize, String> {
+    header
+      .iter()
+      .position(|h| h.trim() == name)
+      .ok_or(format!("Column '{}' is not found in header.", name))
+  };
+  let fumen_column = column(FUMEN_COLUMN)?;
+  let use_column = column(USE_COLUMN)?;
+  let operations_column = column(OPERATIONS_COLUMN).ok();
+
+  let decoder = TetfuDecoder::new();
+  let mut solutions = vec![];
+  for (i, line) in lines {
+    let row = split_row(line);
+    if row.len() != header.len() {
+      return Err(format!(
+        "Malformed row at line {}: expected {} columns but found {}.",
+        i + 1,
+        header.len(),
+        row.len()
+      ));
+    }
+
+    let to_row_error = |e: String| format!("Malformed row at line {}: {}", i + 1, e);
+
+    let fumen = String::from(row[fumen_column].trim());
+    let used = row[use_column]
+      .trim()
+      .chars()
+      .map(Tetromino::try_from)
+      .collect::<Result<Vec<_>, _>>()
+      .map_err(to_row_error)?;
+    let field = decoder.decode(fumen.clone()).map_err(to_row_error)?.field;
+    let operations = match operations_column {
+      Some(operations_column) => row[operations_column]
+        .split(';')
+        .filter(|op| !op.trim().is_empty())
+        .map(SfinderOperation::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(to_row_error)?,
+      None => operations_from_field(&field).map_err(to_row_error)?,
+    };
+
+    let mut solution = SfinderSolution {
+      fumen,
+      used,
+      operations,
+      field,
+      deletes_line: false,
+    };
+    solution.deletes_line = deletes_line_before_last(&solution);
+    solutions.push(solution);
+  }
+
+  Ok(solutions)
+}
+
+// sfinder draws each placed piece in its own colour and leaves the initial field grey. Pieces of
+// the same type may touch, so their cells are split by trying each rotation at the lowest cell.
+fn operations_from_field(field: &Field) -> Result<Vec<SfinderOperation>, String> {
+  let mut operations = vec![];
+  for &r#type in &TYPES {
+    let cells: Vec<XY> = (0..MAX_FIELD_HEIGHT)
+      .flat_map(|y| (0..MAX_FIELD_WIDTH).map(move |x| (x, y)))
+      .filter(|&(x, y)| field.get_cell(x, y) == FieldCellValue::from(r#type))
+      .collect();
+    if !split_into_operations(r#type, &cells, &mut operations) {
+      return Err(format!("Cannot split {:?} cells into pieces.", r#type));
+    }
+  }
+
+  // Lower pieces first, so that deletes_line_before_last sees a placeable order.
+  operations.sort_by_key(|op| {
+    let blocks = op.blocks();
+    let bottom = blocks.iter().map(|&(_, y)| y).min();
+    let left = blocks.iter().map(|&(x, _)| x).min();
+    (bottom, left)
+  });
+  Ok(operations)
+}
+
+fn split_into_operations(
+  r#type: Tetromino,
+  cells: &[XY],
+  operations: &mut Vec<SfinderOperation>,
+) -> bool {
+  let lowest = match cells.iter().min_by_key(|&&(x, y)| (y, x)) {
+    Some(&x) => x,
+    None => return true,
+  };
+
+  for &direction in &[
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+  ] {
+    let mut placement = Placement {
+      r#type,
+      direction,
+      x: 0,
+      y: 0,
+    };
+    let (x, y) = placement
+      .blocks(CoordinateSystem::Sfinder)
+      .into_iter()
+      .min_by_key(|&(x, y)| (y, x))
+      .unwrap();
+    placement.x = lowest.0 - x;
+    placement.y = lowest.1 - y;
+
+    let blocks = placement.blocks(CoordinateSystem::Sfinder);
+    if !blocks.iter().all(|block| cells.contains(block)) {
+      continue;
+    }
+
+    let rest: Vec<XY> = cells
+      .iter()
+      .copied()
+      .filter(|cell| !blocks.contains(cell))
+      .collect();
+    operations.push(SfinderOperation::from_placement(&placement));
+    if split_into_operations(r#type, &rest, operations) {
+      return true;
+    }
+    operations.pop();
+  }
+
+  false
+}
+
+pub fn deletes_line_before_last(solution: &SfinderSolution) -> bool {
+  let operations = &solution.operations;
+  let mut field = solution.initial_field();
+  let last = operations.len().saturating_sub(1);
+  for op in operations.iter().take(last) {
+    for (x, y) in op.blocks() {
+      field.set_cell(x, y, FieldCellValue::from(op.r#type));
+    }
+
+    let is_filled_line =
+      |y: i32| (0..MAX_FIELD_WIDTH).all(|x| field.get_cell(x, y) != FieldCellValue::None);
+    if (0..MAX_FIELD_HEIGHT).any(is_filled_line) {
+      return true;
+    }
+  }
+
+  false
+}
+
+fn split_row(line: &str) -> Vec<String> {
+  let mut values = vec![];
+  let mut value = String::new();
+  let mut in_quotes = false;
+  let mut chars = line.trim_end_matches('\r').chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if in_quotes && chars.peek() == Some(&'"') => {
+        value.push('"');
+        chars.next();
+      }
+      '"' => in_quotes = !in_quotes,
+      ',' if !in_quotes => values.push(std::mem::take(&mut value)),
+      _ => value.push(c),
+    }
+  }
+  values.push(value);
+
+  values
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn operation_blocks() {
+    let op = SfinderOperation {
+      r#type: Tetromino::T,
+      rotation: SfinderRotation::Right,
+      x: 1,
+      y: 1,
+    };
+    let mut blocks = op.blocks();
+    blocks.sort();
+    assert_eq!(blocks, vec![(1, 0), (1, 1), (1, 2), (2, 1)]);
+
+    let op = SfinderOperation {
+      r#type: Tetromino::I,
+      rotation: SfinderRotation::Reverse,
+      x: 2,
+      y: 0,
+    };
+    let mut blocks = op.blocks();
+    blocks.sort();
+    assert_eq!(blocks, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+  }
+
+  #[test]
+  fn operation_blocks_should_match_fumen_cells() {
+    // The same T pieces as in coordinates::tests::convert_to_fumen_cells.
+    let decoded = TetfuDecoder::new()
+      .decode(String::from(
+        "v115@0gwwHexwIewwBewwFewwBe0wAeywAewwBewwLeAgH",
+      ))
+      .unwrap();
+    let mut field = Field::new();
+    for op in &["T-Spawn-1-0", "T-Right-4-1", "T-Reverse-7-1", "T-Left-1-3"] {
+      for (x, y) in SfinderOperation::try_from(*op).unwrap().blocks() {
+        field.set_cell(x, y, FieldCellValue::T);
+      }
+    }
+    assert_eq!(decoded.field, field);
+  }
+
+  #[test]
+  fn parse_operation() {
+    assert_eq!(
+      SfinderOperation::try_from("L-Reverse-8-1"),
+      Ok(SfinderOperation {
+        r#type: Tetromino::L,
+        rotation: SfinderRotation::Reverse,
+        x: 8,
+        y: 1,
+      })
+    );
+    assert_eq!(
+      SfinderOperation::try_from("L-Reverse-8"),
+      Err(String::from("Invalid operation 'L-Reverse-8'"))
+    );
+    assert_eq!(
+      SfinderOperation::try_from("L-Up-8-1"),
+      Err(String::from("Cannot convert from 'Up' to rotation"))
+    );
+  }
+
+  #[test]
+  fn parse_should_read_solutions() {
+    let csv = "fumen,use,operations\n\
+               v115@9gwhIewhIewhIewhSeAAA,I,I-Left-0-1\n\
+               v115@9gwhIewhIewhIewhSeAAA,\"I\",\"I-Right-0-2\"\n";
+
+    let solutions = parse(csv).unwrap();
+    assert_eq!(solutions.len(), 2);
+    assert_eq!(solutions[0].used, vec![Tetromino::I]);
+    assert_eq!(solutions[0].settles(), "I");
+    assert_eq!(solutions[0].field.get_cell(0, 3), FieldCellValue::I);
+    assert_eq!(solutions[0].deletes_line, false);
+    assert_eq!(solutions[1].operations[0].rotation, SfinderRotation::Right);
+  }
+
+  #[test]
+  fn parse_should_read_operations_from_fumen() {
+    // Two O pieces side by side next to an I, without an operations column.
+    let csv = "fumen,use\nv115@RhTpF8TpzhB8JeAgH,OOI\n";
+
+    let solutions = parse(csv).unwrap();
+    assert_eq!(
+      solutions[0].operations,
+      vec![
+        SfinderOperation::try_from("O-Spawn-0-0").unwrap(),
+        SfinderOperation::try_from("O-Spawn-2-0").unwrap(),
+        SfinderOperation::try_from("I-Spawn-5-0").unwrap(),
+      ]
+    );
+    assert_eq!(
+      solutions[0].initial_field().get_cell(0, 0),
+      FieldCellValue::None
+    );
+    assert_eq!(
+      solutions[0].initial_field().get_cell(8, 0),
+      FieldCellValue::Garbage
+    );
+
+    assert_eq!(
+      parse("fumen,use\nv115@bhyhQeAgH,I\n"),
+      Err(String::from(
+        "Malformed row at line 2: Cannot split I cells into pieces."
+      ))
+    );
+  }
+
+  #[test]
+  fn parse_should_detect_line_deletion() {
+    // An I is laid on the bottom row first, so the row is cleared before the last piece.
+    let csv = "operations,fumen,use\n\
+               I-Spawn-1-0;I-Spawn-5-0;O-Spawn-8-0,v115@,IIO\n";
+
+    let solutions = parse(&csv.replace("v115@", "v115@vhAAAA")).unwrap();
+    assert_eq!(solutions[0].deletes_line, false);
+
+    let mut field = Field::new();
+    for x in 0..MAX_FIELD_WIDTH {
+      field.set_cell(x, 0, FieldCellValue::Garbage);
+    }
+    field.set_cell(8, 1, FieldCellValue::Garbage);
+    let operations = vec![
+      SfinderOperation::try_from("O-Spawn-8-0").unwrap(),
+      SfinderOperation::try_from("I-Spawn-1-1").unwrap(),
+    ];
+    let solution = SfinderSolution {
+      fumen: String::new(),
+      used: vec![Tetromino::O, Tetromino::I],
+      operations,
+      field,
+      deletes_line: false,
+    };
+    assert_eq!(deletes_line_before_last(&solution), true);
+  }
+
+  #[test]
+  fn parse_should_fail_for_malformed_rows() {
+    assert_eq!(
+      parse("use,operations\nI,I-Spawn-1-0\n"),
+      Err(String::from("Column 'fumen' is not found in header."))
+    );
+    assert_eq!(
+      parse("fumen,use,operations\nv115@vhAAAA,I\n"),
+      Err(String::from(
+        "Malformed row at line 2: expected 3 columns but found 2."
+      ))
+    );
+    assert_eq!(
+      parse("fumen,use,operations\nv115@vhAAAA,X,I-Spawn-1-0\n"),
+      Err(String::from(
+        "Malformed row at line 2: Cannot convert from 'X' to Tetromino"
+      ))
+    );
+    assert_eq!(
+      parse("fumen,use,operations\nv115@vhAAAA,I,I-Spawn-1\n"),
+      Err(String::from(
+        "Malformed row at line 2: Invalid operation 'I-Spawn-1'"
+      ))
+    );
+  }
+
+  #[test]
+  fn parse_should_return_empty_for_empty_content() {
+    assert_eq!(parse(""), Ok(vec![]));
+    assert_eq!(parse("fumen,use,operations\n"), Ok(vec![]));
+  }
+}
@@ -0,0 +1,159 @@
+use crate::tetsimu2::core::DropType;
+
+const DROP_OPTION: &str = "--drop";
+const KICKS_OPTION: &str = "--kicks";
+pub const KICKS_180: &str = "@nullpomino180";
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SfinderFeatures {
+  pub drop_types: Option<Vec<String>>,
+  pub supports_kicks: bool,
+}
+
+impl SfinderFeatures {
+  pub fn from_help(help: &str) -> Self {
+    let lines: Vec<_> = help.lines().map(|line| line.trim()).collect();
+
+    let drop_types = lines
+      .iter()
+      .position(|line| line.contains(DROP_OPTION))
+      .map(|start| {
+        let description = lines[start..]
+          .iter()
+          .enumerate()
+          .take_while(|(i, line)| *i == 0 || !line.starts_with('-'))
+          .map(|(_, line)| *line)
+          .collect::<Vec<_>>()
+          .join(" ");
+
+        description
+          .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+          .filter(|word| DROP_TYPES.iter().any(|drop_type| drop_type == word))
+          .map(String::from)
+          .collect::<Vec<_>>()
+      })
+      .filter(|drop_types| !drop_types.is_empty());
+
+    SfinderFeatures {
+      drop_types,
+      supports_kicks: lines.iter().any(|line| line.contains(KICKS_OPTION)),
+    }
+  }
+
+  pub fn validate_drop_type(&self, drop_type: DropType) -> Result<(), String> {
+    let drop_types = match &self.drop_types {
+      Some(x) => x,
+      None => return Ok(()),
+    };
+
+    let option = drop_option(drop_type);
+    if !drop_types.iter().any(|x| x == option) {
+      return Err(format!(
+        "Installed solution finder does not support drop type '{}'.",
+        option
+      ));
+    }
+
+    if drop_type == DropType::OneHundredEighty && !self.supports_kicks {
+      return Err(String::from(
+        "Installed solution finder does not support kick tables required for 180 rotation.",
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+const DROP_TYPES: [&str; 8] = [
+  "softdrop",
+  "harddrop",
+  "180",
+  "t-softdrop",
+  "any-tspin",
+  "tss",
+  "tsd",
+  "tst",
+];
+
+pub fn drop_option(drop_type: DropType) -> &'static str {
+  match drop_type {
+    DropType::SoftDrop => DROP_TYPES[0],
+    DropType::HardDrop => DROP_TYPES[1],
+    DropType::OneHundredEighty => DROP_TYPES[2],
+    DropType::TSoftDrop => DROP_TYPES[3],
+    DropType::AnyTSpin => DROP_TYPES[4],
+    DropType::Tss => DROP_TYPES[5],
+    DropType::Tsd => DROP_TYPES[6],
+    DropType::Tst => DROP_TYPES[7],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_help() {
+    let help = r#"
+usage: path [options]
+ -c,--clear-line <num>     Specify clear line
+ -d,--drop <string>        Specify drop: softdrop, harddrop, 180,
+                           t-softdrop, any-tspin, tss, tsd, tst
+ -K,--kicks <string>       Specify kicks file or preset
+ -H,--hold <string>        Specify hold: use, avoid
+"#;
+
+    let features = SfinderFeatures::from_help(help);
+    assert_eq!(
+      features.drop_types,
+      Some(vec![
+        String::from("softdrop"),
+        String::from("harddrop"),
+        String::from("180"),
+        String::from("t-softdrop"),
+        String::from("any-tspin"),
+        String::from("tss"),
+        String::from("tsd"),
+        String::from("tst"),
+      ])
+    );
+    assert_eq!(features.supports_kicks, true);
+    assert_eq!(
+      features.validate_drop_type(DropType::OneHundredEighty),
+      Ok(())
+    );
+  }
+
+  #[test]
+  fn validate_drop_type_with_old_sfinder() {
+    let help = r#"
+ -d,--drop <string>        Specify drop: softdrop, harddrop, tss, tsd, tst
+ -H,--hold <string>        Specify hold: use, avoid
+"#;
+
+    let features = SfinderFeatures::from_help(help);
+    assert_eq!(features.validate_drop_type(DropType::Tsd), Ok(()));
+    assert_eq!(
+      features.validate_drop_type(DropType::TSoftDrop),
+      Err(String::from(
+        "Installed solution finder does not support drop type 't-softdrop'."
+      ))
+    );
+    assert_eq!(
+      features
+        .validate_drop_type(DropType::OneHundredEighty)
+        .is_err(),
+      true
+    );
+  }
+
+  #[test]
+  fn validate_drop_type_with_unknown_help() {
+    let features = SfinderFeatures::from_help("");
+    assert_eq!(features.validate_drop_type(DropType::AnyTSpin), Ok(()));
+    assert_eq!(
+      features.validate_drop_type(DropType::OneHundredEighty),
+      Ok(())
+    );
+  }
+}
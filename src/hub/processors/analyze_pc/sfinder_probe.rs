@@ -0,0 +1,209 @@
+use crate::hub::processors::analyze_pc::sfinder_features::SfinderFeatures;
+use crate::settings::SolutionFinderSettings;
+use crate::settings::SolverBackendKind;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+pub const MAIN_JAR: &str = "sfinder.jar";
+
+#[derive(Debug, Default)]
+pub struct SfinderProbe {
+  pub java: String,
+  pub java_version: Option<String>,
+  pub version: Option<String>,
+  pub features: Option<SfinderFeatures>,
+  pub problems: Vec<String>,
+  // Set when another solver backend is selected, so there is nothing to check.
+  pub skipped: bool,
+}
+
+impl SfinderProbe {
+  pub fn run(settings: &SolutionFinderSettings) -> Self {
+    let mut probe = SfinderProbe {
+      java: java_path(settings),
+      ..Default::default()
+    };
+
+    if settings.backend != SolverBackendKind::Sfinder {
+      probe.skipped = true;
+      return probe;
+    }
+
+    match Command::new(&probe.java).arg("-version").output() {
+      Ok(output) => {
+        probe.java_version = parse_version(&String::from_utf8_lossy(&output.stderr))
+          .or_else(|| parse_version(&String::from_utf8_lossy(&output.stdout)));
+      }
+      Err(e) => {
+        probe.problems.push(format!(
+          "Java runtime '{}' cannot be executed. {}",
+          probe.java, e
+        ));
+        return probe;
+      }
+    }
+
+    let sf_root = match &settings.path {
+      Some(x) => x,
+      None => {
+        probe
+          .problems
+          .push(String::from("Solution finder settings is not set."));
+        return probe;
+      }
+    };
+
+    if !Path::new(sf_root).join(MAIN_JAR).exists() {
+      probe
+        .problems
+        .push(format!("Cannot find {} in '{}'.", MAIN_JAR, sf_root));
+      return probe;
+    }
+
+    match sfinder_command(settings).arg("-v").output() {
+      Ok(output) => {
+        probe.version = parse_version(&combine_output(&output.stdout, &output.stderr));
+        if probe.version.is_none() {
+          probe
+            .problems
+            .push(String::from("Could not detect solution finder version."));
+        }
+      }
+      Err(e) => probe.problems.push(e.to_string()),
+    }
+
+    match sfinder_command(settings).arg("path").arg("-h").output() {
+      Ok(output) => {
+        let help = combine_output(&output.stdout, &output.stderr);
+        let features = SfinderFeatures::from_help(&help);
+        if features.drop_types.is_none() {
+          probe
+            .problems
+            .push(String::from("Could not detect solution finder features."));
+        }
+        probe.features = Some(features);
+      }
+      Err(e) => probe.problems.push(e.to_string()),
+    }
+
+    probe
+  }
+}
+
+impl fmt::Display for SfinderProbe {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "[solution_finder probe]")?;
+    if self.skipped {
+      return writeln!(f, "  skipped: solution finder backend is not selected.");
+    }
+
+    writeln!(
+      f,
+      "  java: {} ({})",
+      self.java,
+      self.java_version.as_deref().unwrap_or("unknown")
+    )?;
+    writeln!(
+      f,
+      "  version: {}",
+      self.version.as_deref().unwrap_or("unknown")
+    )?;
+    if let Some(drop_types) = self.features.as_ref().and_then(|x| x.drop_types.as_ref()) {
+      writeln!(f, "  drop: {}", drop_types.join(", "))?;
+    }
+
+    for problem in &self.problems {
+      writeln!(f, "  WARNING: {}", problem)?;
+    }
+
+    Ok(())
+  }
+}
+
+pub fn java_path(settings: &SolutionFinderSettings) -> String {
+  if let Some(java_path) = &settings.java_path {
+    return java_path.clone();
+  }
+
+  if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let path = PathBuf::from(java_home).join("bin").join(java);
+    if path.exists() {
+      return path.to_string_lossy().into_owned();
+    }
+  }
+
+  String::from("java")
+}
+
+pub fn sfinder_command(settings: &SolutionFinderSettings) -> Command {
+  let mut command = Command::new(java_path(settings));
+  command.args(&settings.jvm_args).arg("-jar").arg(MAIN_JAR);
+  if let Some(path) = &settings.path {
+    command.current_dir(path);
+  }
+
+  command
+}
+
+fn combine_output(stdout: &[u8], stderr: &[u8]) -> String {
+  format!(
+    "{}\n{}",
+    String::from_utf8_lossy(stdout),
+    String::from_utf8_lossy(stderr)
+  )
+}
+
+fn parse_version(text: &str) -> Option<String> {
+  text
+    .lines()
+    .filter(|line| line.to_lowercase().contains("version"))
+    .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == '"' || c == ':' || c == '='))
+    .find(|word| word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.'))
+    .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_version_of_java() {
+    let text = r#"openjdk version "17.0.1" 2021-10-19
+OpenJDK Runtime Environment (build 17.0.1+12-39)"#;
+    assert_eq!(parse_version(text), Some(String::from("17.0.1")));
+  }
+
+  #[test]
+  fn parse_version_of_sfinder() {
+    assert_eq!(parse_version("Version: 1.42\n"), Some(String::from("1.42")));
+    assert_eq!(parse_version("usage: path [options]\n"), None);
+  }
+
+  #[test]
+  fn run_should_skip_other_backends() {
+    let settings = SolutionFinderSettings {
+      backend: SolverBackendKind::Native,
+      java_path: Some(String::from("/not/found/java")),
+      ..Default::default()
+    };
+    let probe = SfinderProbe::run(&settings);
+    assert_eq!(probe.skipped, true);
+    assert_eq!(probe.problems, Vec::<String>::new());
+    assert_eq!(
+      probe.to_string(),
+      "[solution_finder probe]\n  skipped: solution finder backend is not selected.\n"
+    );
+  }
+
+  #[test]
+  fn java_path_from_settings() {
+    let settings = SolutionFinderSettings {
+      java_path: Some(String::from("/opt/java/bin/java")),
+      ..Default::default()
+    };
+    assert_eq!(java_path(&settings), "/opt/java/bin/java");
+  }
+}
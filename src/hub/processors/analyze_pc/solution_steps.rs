@@ -0,0 +1,418 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderOperation;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::router::RouteSearcher;
+use crate::tetsimu2::router::SearchRouteAction;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlacementStep {
+  pub hold: bool,
+  pub piece: CurrentTetromino,
+  pub route: Vec<SearchRouteAction>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SolutionSteps {
+  pub steps: Vec<PlacementStep>,
+  pub reachable: bool,
+}
+
+// Operations are given in the coordinates of the solved field, so cleared rows stay in place
+// and are skipped when the field is looked at from the player's point of view.
+pub struct PlacingState {
+  pub field: Field,
+  pub cleared: [bool; MAX_FIELD_HEIGHT as usize],
+  pub used: Vec<bool>,
+}
+
+impl PlacingState {
+  pub fn new(solution: &SfinderSolution) -> Self {
+    PlacingState {
+      field: solution.initial_field(),
+      cleared: [false; MAX_FIELD_HEIGHT as usize],
+      used: vec![false; solution.operations.len()],
+    }
+  }
+
+  pub fn is_supported(&self, op: &SfinderOperation) -> bool {
+    let blocks = op.blocks();
+    blocks.iter().any(|&(x, y)| {
+      let below = (0..y).rev().find(|&y| !self.cleared[y as usize]);
+      match below {
+        Some(below) => {
+          !blocks.contains(&(x, below)) && self.field.get_cell(x, below) != FieldCellValue::None
+        }
+        None => true,
+      }
+    })
+  }
+
+  pub fn place(&mut self, op: &SfinderOperation) -> u32 {
+    for (x, y) in op.blocks() {
+      self.field.set_cell(x, y, FieldCellValue::from(op.r#type));
+    }
+
+    let mut cleared_lines = 0;
+    for y in 0..MAX_FIELD_HEIGHT {
+      if self.cleared[y as usize] {
+        continue;
+      }
+
+      if (0..MAX_FIELD_WIDTH).all(|x| self.field.get_cell(x, y) != FieldCellValue::None) {
+        self.cleared[y as usize] = true;
+        cleared_lines += 1;
+      }
+    }
+
+    cleared_lines
+  }
+
+  pub fn live_field(&self) -> Field {
+    let mut field = Field::new();
+    let mut live_y = 0;
+    for y in 0..MAX_FIELD_HEIGHT {
+      if self.cleared[y as usize] {
+        continue;
+      }
+
+      for x in 0..MAX_FIELD_WIDTH {
+        field.set_cell(x, live_y, self.field.get_cell(x, y));
+      }
+      live_y += 1;
+    }
+
+    field
+  }
+
+  pub fn to_current_tetromino(&self, op: &SfinderOperation) -> Option<CurrentTetromino> {
+    let mut targets = vec![];
+    for (x, y) in op.blocks() {
+      if !(0..MAX_FIELD_HEIGHT).contains(&y) || self.cleared[y as usize] {
+        return None;
+      }
+
+      let cleared_below = (0..y).filter(|&y| self.cleared[y as usize]).count() as i32;
+      targets.push((x, y - cleared_below));
+    }
+
+    let direction = Direction::from(op.rotation);
+    let directions = [
+      direction,
+      Direction::Up,
+      Direction::Right,
+      Direction::Down,
+      Direction::Left,
+    ];
+
+    directions.iter().find_map(|&direction| {
+      Placement::from_blocks(CoordinateSystem::Tetsimu2, op.r#type, direction, &targets)
+        .map(|x| x.to_current_tetromino())
+    })
+  }
+}
+
+pub struct Hand {
+  pub next: usize,
+  pub current: Option<Tetromino>,
+  pub hold: Option<Tetromino>,
+}
+
+impl Hand {
+  pub fn new(queue: &[Tetromino]) -> Self {
+    Hand {
+      next: 1,
+      current: queue.first().copied(),
+      hold: None,
+    }
+  }
+
+  pub fn placed(&self, queue: &[Tetromino]) -> Self {
+    Hand {
+      next: self.next + 1,
+      current: queue.get(self.next).copied(),
+      hold: self.hold,
+    }
+  }
+
+  pub fn held(&self, queue: &[Tetromino]) -> Self {
+    match self.hold {
+      Some(_) => Hand {
+        next: self.next,
+        current: self.hold,
+        hold: self.current,
+      },
+      None => Hand {
+        next: self.next + 1,
+        current: queue.get(self.next).copied(),
+        hold: self.current,
+      },
+    }
+  }
+}
+
+struct StepSearcher<'a> {
+  operations: &'a [SfinderOperation],
+  queue: &'a [Tetromino],
+  use_hold: bool,
+  drop_type: Option<DropType>,
+}
+
+pub fn solution_steps(
+  solution: &SfinderSolution,
+  queue: &[Tetromino],
+  use_hold: bool,
+  drop_type: DropType,
+) -> SolutionSteps {
+  let searcher = StepSearcher {
+    operations: &solution.operations,
+    queue,
+    use_hold,
+    drop_type: Some(drop_type),
+  };
+  if let Some(steps) = searcher.search(solution) {
+    return SolutionSteps {
+      steps,
+      reachable: true,
+    };
+  }
+
+  let searcher = StepSearcher {
+    drop_type: None,
+    ..searcher
+  };
+  SolutionSteps {
+    steps: searcher.search(solution).unwrap_or_default(),
+    reachable: false,
+  }
+}
+
+impl<'a> StepSearcher<'a> {
+  fn search(&self, solution: &SfinderSolution) -> Option<Vec<PlacementStep>> {
+    let mut state = PlacingState::new(solution);
+    let hand = Hand::new(self.queue);
+    let mut steps = vec![];
+
+    if self.search_steps(&mut state, &hand, false, &mut steps) {
+      Some(steps)
+    } else {
+      None
+    }
+  }
+
+  fn search_steps(
+    &self,
+    state: &mut PlacingState,
+    hand: &Hand,
+    held: bool,
+    steps: &mut Vec<PlacementStep>,
+  ) -> bool {
+    if steps.len() == self.operations.len() {
+      return true;
+    }
+
+    let current = match hand.current {
+      Some(x) => x,
+      None if hand.hold.is_some() && !held => {
+        return self.search_steps(state, &hand.held(self.queue), true, steps);
+      }
+      None => return false,
+    };
+
+    for (i, op) in self.operations.iter().enumerate() {
+      if state.used[i] || op.r#type != current || !state.is_supported(op) {
+        continue;
+      }
+
+      let piece = match state.to_current_tetromino(op) {
+        Some(x) => x,
+        None => continue,
+      };
+
+      let route = match self.drop_type {
+        Some(drop_type) => {
+          let field = state.live_field();
+          let route_searcher = RouteSearcher { field: &field };
+          match route_searcher.search_route_with_drop_type(
+            spawn(current, &field),
+            &piece,
+            drop_type,
+          ) {
+            Some(route) => route,
+            None => continue,
+          }
+        }
+        None => vec![],
+      };
+
+      let prev_field = state.field.clone();
+      let prev_cleared = state.cleared;
+      state.place(op);
+      state.used[i] = true;
+      steps.push(PlacementStep {
+        hold: held,
+        piece,
+        route,
+      });
+
+      if self.search_steps(state, &hand.placed(self.queue), false, steps) {
+        return true;
+      }
+
+      steps.pop();
+      state.used[i] = false;
+      state.field = prev_field;
+      state.cleared = prev_cleared;
+    }
+
+    if self.use_hold && !held && self.search_steps(state, &hand.held(self.queue), true, steps) {
+      return true;
+    }
+
+    false
+  }
+}
+
+pub fn spawn(r#type: Tetromino, field: &Field) -> CurrentTetromino {
+  let mut piece = CurrentTetromino {
+    r#type,
+    direction: Direction::Up,
+    x: 4,
+    y: 19,
+  };
+
+  if field.is_overlapped(&piece) {
+    piece.y += 1;
+  }
+
+  piece
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use std::convert::TryFrom;
+
+  fn make_solution(rows: &[&str], operations: &[&str]) -> SfinderSolution {
+    let operations: Vec<_> = operations
+      .iter()
+      .map(|op| SfinderOperation::try_from(*op).unwrap())
+      .collect();
+
+    SfinderSolution {
+      fumen: String::new(),
+      used: operations.iter().map(|op| op.r#type).collect(),
+      operations,
+      field: make_field(&rows.concat()),
+      deletes_line: false,
+    }
+  }
+
+  #[test]
+  fn to_current_tetromino() {
+    let solution = make_solution(&["IIIIGGGGGG"], &["I-Spawn-1-0"]);
+    let state = PlacingState::new(&solution);
+
+    for op in &["I-Spawn-1-0", "I-Reverse-2-0"] {
+      let op = SfinderOperation::try_from(*op).unwrap();
+      let piece = state.to_current_tetromino(&op).unwrap();
+      let mut blocks = piece.blocks();
+      blocks.sort();
+      assert_eq!(blocks, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+    }
+  }
+
+  #[test]
+  fn to_current_tetromino_should_skip_cleared_rows() {
+    #[rustfmt::skip]
+    let rows = [
+      "OOGGGGGGGG",
+      "OOGGGGGGGG",
+      "IIIIGGGGGG",
+    ];
+    let solution = make_solution(&rows, &["I-Spawn-1-0", "O-Spawn-0-1"]);
+    let mut state = PlacingState::new(&solution);
+    state.place(&solution.operations[0]);
+
+    let piece = state.to_current_tetromino(&solution.operations[1]).unwrap();
+    let mut blocks = piece.blocks();
+    blocks.sort();
+    assert_eq!(blocks, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+  }
+
+  #[test]
+  fn solution_steps_in_queue_order() {
+    #[rustfmt::skip]
+    let rows = [
+      "OOGGGGGGGG",
+      "OOGGGGGGGG",
+      "IIIIGGGGGG",
+    ];
+    let solution = make_solution(&rows, &["O-Spawn-0-1", "I-Spawn-1-0"]);
+
+    let actual = solution_steps(
+      &solution,
+      &[Tetromino::I, Tetromino::O],
+      false,
+      DropType::SoftDrop,
+    );
+    assert_eq!(actual.reachable, true);
+    assert_eq!(actual.steps.len(), 2);
+    assert_eq!(actual.steps[0].piece.r#type, Tetromino::I);
+    assert_eq!(actual.steps[0].hold, false);
+    assert_eq!(actual.steps[1].piece.r#type, Tetromino::O);
+    assert_eq!(actual.steps[1].piece.y, 0);
+  }
+
+  #[test]
+  fn solution_steps_with_hold() {
+    #[rustfmt::skip]
+    let rows = [
+      "OOGGGGGGGG",
+      "OOGGGGGGGG",
+      "IIIIGGGGGG",
+    ];
+    let solution = make_solution(&rows, &["O-Spawn-0-1", "I-Spawn-1-0"]);
+    let queue = [Tetromino::O, Tetromino::I];
+
+    let actual = solution_steps(&solution, &queue, true, DropType::SoftDrop);
+    assert_eq!(actual.reachable, true);
+    assert_eq!(actual.steps[0].piece.r#type, Tetromino::I);
+    assert_eq!(actual.steps[0].hold, true);
+    assert_eq!(actual.steps[1].piece.r#type, Tetromino::O);
+    assert_eq!(actual.steps[1].hold, true);
+
+    let actual = solution_steps(&solution, &queue, false, DropType::SoftDrop);
+    assert_eq!(actual, SolutionSteps::default());
+  }
+
+  #[test]
+  fn solution_steps_should_report_unreachable_placements() {
+    #[rustfmt::skip]
+    let rows = [
+      "GGGGGGGGNN",
+      "GGGGGGGGNN",
+      "GGGGGGGGGG",
+      "GGGGGGGGGG",
+      "GGGGGGGGGG",
+      "GGGGGGGGGG",
+      "GGGGGGGGOO",
+      "GGGGGGGGOO",
+    ];
+    let solution = make_solution(&rows, &["O-Spawn-8-0"]);
+
+    let actual = solution_steps(&solution, &[Tetromino::O], false, DropType::SoftDrop);
+    assert_eq!(actual.reachable, false);
+    assert_eq!(actual.steps.len(), 1);
+    assert_eq!(actual.steps[0].route, vec![]);
+  }
+}
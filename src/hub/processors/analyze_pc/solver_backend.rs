@@ -0,0 +1,65 @@
+use crate::hub::processors::analyze_pc::native_backend::NativeBackend;
+use crate::hub::processors::analyze_pc::sfinder_backend::SfinderBackend;
+use crate::hub::processors::analyze_pc::sfinder_csv::SfinderSolution;
+use crate::hub::shared_context::SharedContext;
+use crate::settings::Settings;
+use crate::settings::SolverBackendKind;
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::field::Field;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PcSolverRequest {
+  pub field: Field,
+  pub nexts: String,
+  pub clear_line: i32,
+  pub use_hold: bool,
+  pub drop_type: DropType,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PcSolverOutput {
+  pub message: String,
+  pub minimal: Vec<SfinderSolution>,
+  pub unique: Vec<SfinderSolution>,
+}
+
+pub struct SolveControl<'a> {
+  pub is_halted: &'a AtomicBool,
+  pub log: &'a dyn Fn(&str),
+}
+
+impl<'a> SolveControl<'a> {
+  pub fn is_halted(&self) -> bool {
+    self.is_halted.load(Ordering::Relaxed)
+  }
+
+  pub fn log(&self, message: &str) {
+    (self.log)(message);
+  }
+}
+
+pub trait PcSolverBackend: Send + Sync {
+  fn validate(&self, request: &PcSolverRequest) -> Result<(), String>;
+
+  fn fingerprint(&self) -> Option<String>;
+
+  fn solve(
+    &self,
+    request: &PcSolverRequest,
+    control: &SolveControl<'_>,
+  ) -> Result<PcSolverOutput, String>;
+
+  fn halt(&self) {}
+}
+
+pub fn create_backend(
+  settings: &Arc<Settings>,
+  context: &Arc<SharedContext>,
+) -> Box<dyn PcSolverBackend> {
+  match settings.solution_finder.backend {
+    SolverBackendKind::Sfinder => Box::new(SfinderBackend::new(settings, context)),
+    SolverBackendKind::Native => Box::new(NativeBackend),
+  }
+}
@@ -0,0 +1,335 @@
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageRes;
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageResBody;
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageResBodyCell;
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageResBodySlot;
+use crate::hub::messages::hub::analyze_tspin::AnalyzeTspinMessageResResult;
+use crate::hub::messages::hub::analyze_tspin::TspinKind;
+use crate::hub::messages::hub::header::HubMessageResHeader;
+use crate::hub::messages::hub::steps::Step;
+use crate::hub::messages::hub::HubMessage;
+use crate::hub::messages::hub::VERSION;
+use crate::hub::messages::tetsimu2::analyze_tspin::AnalyzeTspinMessageReq;
+use crate::hub::processors::analyze_pc::solution_steps::spawn;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_INNER_FIELD_HEIGHT;
+use crate::tetsimu2::core::XY;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::placements::placements;
+use crate::tetsimu2::router::RouteSearcher;
+use crate::tetsimu2::router::SearchRouteAction;
+use log::debug;
+use num_traits::FromPrimitive;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TspinSlot {
+  pub kind: TspinKind,
+  pub piece: CurrentTetromino,
+  pub lines: u32,
+  pub is_reachable: bool,
+  // Empty corner cells an overhang still has to fill.
+  pub missing: Vec<XY>,
+}
+
+pub fn execute(out: &ws::Sender, message: &AnalyzeTspinMessageReq) {
+  let body = match to_field(&message.body.field) {
+    Ok(field) => AnalyzeTspinMessageResBody {
+      succeeded: true,
+      message: String::from(""),
+      slots: tspin_slots(&field).iter().map(to_slot_item).collect(),
+    },
+    Err(e) => AnalyzeTspinMessageResBody {
+      succeeded: false,
+      message: e,
+      slots: vec![],
+    },
+  };
+
+  let response = HubMessage::AnalyzeTspin(AnalyzeTspinMessageRes {
+    header: HubMessageResHeader {
+      version: String::from(VERSION),
+      message_id: Uuid::new_v4().to_string(),
+      request_message_id: message.header.message_id.clone(),
+      result: AnalyzeTspinMessageResResult::Succeeded as i32,
+    },
+    body,
+  });
+
+  let json = serde_json::to_string(&response).unwrap();
+  debug!("response:\n{}", json);
+  out.send(json).ok();
+}
+
+fn to_field(cells: &[u8; 300]) -> Result<Field, String> {
+  let mut field = Field::new();
+  for (i, &cell) in cells.iter().enumerate() {
+    field.data[i] = FromPrimitive::from_u8(cell)
+      .ok_or(format!("Could not convert '{}' to FieldCellValue.", cell))?;
+  }
+  Ok(field)
+}
+
+fn to_slot_item(slot: &TspinSlot) -> AnalyzeTspinMessageResBodySlot {
+  AnalyzeTspinMessageResBodySlot {
+    kind: slot.kind,
+    step: Step {
+      r#type: slot.piece.r#type as u8,
+      dir: slot.piece.direction as u8,
+      x: slot.piece.x as i8,
+      y: slot.piece.y as i8,
+    },
+    lines: slot.lines,
+    reachable: slot.is_reachable,
+    missing: slot
+      .missing
+      .iter()
+      .map(|&(x, y)| AnalyzeTspinMessageResBodyCell {
+        x: x as i8,
+        y: y as i8,
+      })
+      .collect(),
+  }
+}
+
+// Every grounded T placement that clears lines as a T-spin, as the field is or once one more
+// corner is filled.
+pub fn tspin_slots(field: &Field) -> Vec<TspinSlot> {
+  placements(field, Tetromino::T, MAX_INNER_FIELD_HEIGHT)
+    .into_iter()
+    .filter_map(|piece| tspin_slot(field, piece))
+    .collect()
+}
+
+fn tspin_slot(field: &Field, piece: CurrentTetromino) -> Option<TspinSlot> {
+  let (fronts, backs) = corners(piece.direction);
+  let empty_cells = |corners: [XY; 2]| -> Vec<XY> {
+    corners
+      .iter()
+      .map(|&(dx, dy)| (piece.x + dx, piece.y + dy))
+      .filter(|&(x, y)| field.get_cell(x, y) == FieldCellValue::None)
+      .collect()
+  };
+  let empty_fronts = empty_cells(fronts);
+  let empty_backs = empty_cells(backs);
+
+  // Three corners make a T-spin, which is a full one when both corners beside the pointing side
+  // are filled.
+  let (missing, is_full) = match (empty_fronts.len(), empty_backs.len()) {
+    (0, _) if empty_backs.len() < 2 => (vec![], true),
+    (1, 0) => (vec![], false),
+    (1, 1) => (empty_fronts, true),
+    (0, 2) => (vec![overhang(field, &piece, &empty_backs)], true),
+    _ => return None,
+  };
+
+  let mut filled = field.clone();
+  for &(x, y) in &missing {
+    filled.set_cell(x, y, FieldCellValue::Garbage);
+  }
+  let mut settled = filled.clone();
+  settled.settle_tetromino(&piece);
+  let lines = settled.clear_lines();
+
+  let route_searcher = RouteSearcher { field: &filled };
+  let start = spawn(Tetromino::T, &filled);
+  let route = route_searcher.search_route_with_drop_type(start.clone(), &piece, DropType::AnyTSpin);
+  let is_reachable = route.is_some();
+  let is_full =
+    is_full || matches!(&route, Some(route) if ends_with_last_kick(&filled, start, route));
+
+  // A mini never clears three lines.
+  let kind = match (lines, is_full) {
+    (0, _) => return None,
+    (1, true) => TspinKind::Tss,
+    (2, true) => TspinKind::Tsd,
+    (1..=2, false) => TspinKind::Mini,
+    _ => TspinKind::Tst,
+  };
+
+  Some(TspinSlot {
+    kind,
+    piece,
+    lines,
+    is_reachable,
+    missing,
+  })
+}
+
+// Whether the last rotation of the route needs the last kick of its table (the TST kick), which
+// makes a full T-spin whatever the corners.
+fn ends_with_last_kick(
+  field: &Field,
+  start: CurrentTetromino,
+  route: &[SearchRouteAction],
+) -> bool {
+  let mut current = start;
+  let mut is_last_kick = false;
+  for action in route {
+    let before = current.clone();
+    is_last_kick = match action {
+      SearchRouteAction::TurnLeft => {
+        current.turn_left(field);
+        is_kicked_by_last(&before, &current, before.srss_left())
+      }
+      SearchRouteAction::TurnRight => {
+        current.turn_right(field);
+        is_kicked_by_last(&before, &current, before.srss_right())
+      }
+      SearchRouteAction::Turn180 => {
+        current.turn_180(field);
+        false
+      }
+      SearchRouteAction::MoveLeft => {
+        current.move_left(field);
+        false
+      }
+      SearchRouteAction::MoveRight => {
+        current.move_right(field);
+        false
+      }
+      SearchRouteAction::SoftDrop => {
+        current.soft_drop(field);
+        false
+      }
+    };
+  }
+  is_last_kick
+}
+
+fn is_kicked_by_last(before: &CurrentTetromino, after: &CurrentTetromino, kicks: &[XY]) -> bool {
+  kicks.last() == Some(&(after.x - before.x, after.y - before.y))
+}
+
+// Corners beside the pointing side and the ones behind it, relative to the T's centre.
+fn corners(direction: Direction) -> ([XY; 2], [XY; 2]) {
+  match direction {
+    Direction::Up => ([(-1, 1), (1, 1)], [(-1, -1), (1, -1)]),
+    Direction::Right => ([(1, 1), (1, -1)], [(-1, 1), (-1, -1)]),
+    Direction::Down => ([(-1, -1), (1, -1)], [(-1, 1), (1, 1)]),
+    Direction::Left => ([(-1, 1), (-1, -1)], [(1, 1), (1, -1)]),
+  }
+}
+
+// Of two empty back corners, the one that can hang from the wall next to it.
+fn overhang(field: &Field, piece: &CurrentTetromino, empty_backs: &[XY]) -> XY {
+  empty_backs
+    .iter()
+    .copied()
+    .find(|&(x, y)| {
+      let outer_x = x + (x - piece.x);
+      field.get_cell(outer_x, y) != FieldCellValue::None
+    })
+    .unwrap_or(empty_backs[0])
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+
+  fn t(direction: Direction, x: i32, y: i32) -> CurrentTetromino {
+    CurrentTetromino {
+      r#type: Tetromino::T,
+      direction,
+      x,
+      y,
+    }
+  }
+
+  fn find(slots: &[TspinSlot], piece: &CurrentTetromino) -> TspinSlot {
+    slots
+      .iter()
+      .find(|x| x.piece == *piece)
+      .cloned()
+      .unwrap_or_else(|| panic!("{:?} is not found in {:?}", piece, slots))
+  }
+
+  #[test]
+  fn tsd() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "GGNNNNNNNN",
+      "GNNNGGGGGG",
+      "GGNGGGGGGG",
+    ].concat());
+
+    let actual = find(&tspin_slots(&field), &t(Direction::Down, 2, 1));
+    assert_eq!(actual.kind, TspinKind::Tsd);
+    assert_eq!(actual.lines, 2);
+    assert_eq!(actual.is_reachable, true);
+    assert_eq!(actual.missing, vec![]);
+  }
+
+  #[test]
+  fn tsd_without_overhang() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "GNNNNNNNNN",
+      "GNNNGGGGGG",
+      "GGNGGGGGGG",
+    ].concat());
+
+    let actual = find(&tspin_slots(&field), &t(Direction::Down, 2, 1));
+    assert_eq!(actual.kind, TspinKind::Tsd);
+    assert_eq!(actual.is_reachable, true);
+    assert_eq!(actual.missing, vec![(1, 2)]);
+  }
+
+  #[test]
+  fn tst() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "GGNNNNNNNN",
+      "GNNNNNNNNN",
+      "GNGGGGGGGG",
+      "GNNGGGGGGG",
+      "GNGGGGGGGG",
+    ].concat());
+
+    let actual = find(&tspin_slots(&field), &t(Direction::Right, 1, 1));
+    assert_eq!(actual.kind, TspinKind::Tst);
+    assert_eq!(actual.lines, 3);
+    assert_eq!(actual.is_reachable, true);
+  }
+
+  #[test]
+  fn stsd_with_tst_kick() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "GGNNNNNNNN",
+      "GNNNNNNNNN",
+      "GNGGGGGGGG",
+      "GNNGGGGGGG",
+      "GNNGGGGGGG",
+    ].concat());
+
+    // Only one corner beside the pointing side is filled, but the T gets in with the TST kick.
+    let actual = find(&tspin_slots(&field), &t(Direction::Right, 1, 1));
+    assert_eq!(actual.kind, TspinKind::Tsd);
+    assert_eq!(actual.lines, 2);
+    assert_eq!(actual.is_reachable, true);
+    assert_eq!(actual.missing, vec![]);
+  }
+
+  #[test]
+  fn mini() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "NGGGGGGGGG",
+    ].concat());
+
+    let actual = find(&tspin_slots(&field), &t(Direction::Right, 0, 1));
+    assert_eq!(actual.kind, TspinKind::Mini);
+    assert_eq!(actual.lines, 1);
+    assert_eq!(actual.missing, vec![]);
+  }
+
+  #[test]
+  fn no_slots() {
+    assert_eq!(tspin_slots(&Field::new()), vec![]);
+  }
+}
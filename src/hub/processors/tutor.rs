@@ -1,9 +1,17 @@
+use crate::hub::messages::hub::grade::GradeMessage;
+use crate::hub::messages::hub::grade::GradeMessageBody;
+use crate::hub::messages::hub::grade::GradePlacement;
 use crate::hub::messages::hub::header::HubMessageHeader;
 use crate::hub::messages::hub::header::HubMessageResHeader;
 use crate::hub::messages::hub::init_tutor::InitTutorMessageRes;
 use crate::hub::messages::hub::init_tutor::InitTutorMessageResBody;
 use crate::hub::messages::hub::init_tutor::InitTutorMessageResResult;
+use crate::hub::messages::hub::opener::OpenerCandidate;
+use crate::hub::messages::hub::opener::OpenerMessage;
+use crate::hub::messages::hub::opener::OpenerMessageBody;
+use crate::hub::messages::hub::steps::ScoreSource;
 use crate::hub::messages::hub::steps::Step;
+use crate::hub::messages::hub::steps::StepsCandidate;
 use crate::hub::messages::hub::steps::StepsMessage;
 use crate::hub::messages::hub::steps::StepsMessageBody;
 use crate::hub::messages::hub::term_tutor::TermTutorMessageRes;
@@ -17,17 +25,36 @@ use crate::hub::messages::tetsimu2::term_tutor::TermTutorMessageReq;
 use crate::hub::messages::tetsimu2::Tetsimu2Message;
 use crate::hub::processors::tetsimu2_processor::BeforeExecuteResult;
 use crate::hub::processors::tetsimu2_processor::Tetsimu2Processor;
+use crate::hub::processors::tutor::bot_options::cold_clear_options;
+use crate::hub::processors::tutor::bot_options::evaluator;
+use crate::hub::processors::tutor::bot_options::think_budget;
+use crate::hub::processors::tutor::bot_options::ThinkBudget;
+use crate::hub::processors::tutor::bot_session::BotSession;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::candidates::candidates;
+use crate::hub::processors::tutor::candidates::score;
+use crate::hub::processors::tutor::grade::grade;
+use crate::hub::processors::tutor::grade::infer_placement;
+use crate::hub::processors::tutor::grade::Placement;
+use crate::hub::processors::tutor::opener_book::book_move;
+use crate::hub::processors::tutor::opener_catalog::deviated_from;
+use crate::hub::processors::tutor::opener_catalog::recognize;
+use crate::hub::processors::tutor::opener_catalog::OpenerProgress;
+use crate::hub::processors::tutor::tutor_backend::create_backend;
+use crate::hub::processors::tutor::tutor_backend::BotMove;
+use crate::hub::processors::tutor::tutor_backend::Suggestion;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::settings::OpenerOption;
 use crate::settings::Settings;
-use crate::tetsimu2::core::FieldCellValue;
-use crate::tetsimu2::core::Tetromino;
-use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::settings::TutorBackendKind;
+use crate::settings::TutorModeOption;
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
 use cold_clear;
+use cold_clear::evaluation::Standard;
+use cold_clear::moves::MovementMode;
 use core::sync::atomic::{AtomicBool, Ordering};
-use enumset::EnumSet;
-use libtetris::*;
 use log::{debug, error, info};
-use num_traits::FromPrimitive;
-use std::convert::TryFrom;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::thread;
@@ -35,55 +62,20 @@ use std::time::Duration;
 use std::time::Instant;
 use uuid::Uuid;
 
-impl From<Tetromino> for Piece {
-  fn from(r#type: Tetromino) -> Self {
-    match r#type {
-      Tetromino::I => Piece::I,
-      Tetromino::J => Piece::J,
-      Tetromino::L => Piece::L,
-      Tetromino::O => Piece::O,
-      Tetromino::S => Piece::S,
-      Tetromino::T => Piece::T,
-      Tetromino::Z => Piece::Z,
-    }
-  }
-}
-
-impl From<Piece> for Tetromino {
-  fn from(piece: Piece) -> Self {
-    match piece {
-      Piece::I => Tetromino::I,
-      Piece::J => Tetromino::J,
-      Piece::L => Tetromino::L,
-      Piece::O => Tetromino::O,
-      Piece::S => Tetromino::S,
-      Piece::T => Tetromino::T,
-      Piece::Z => Tetromino::Z,
-    }
-  }
-}
-
-impl From<crate::tetsimu2::core::Direction> for RotationState {
-  fn from(dir: crate::tetsimu2::core::Direction) -> Self {
-    match dir {
-      crate::tetsimu2::core::Direction::Up => RotationState::North,
-      crate::tetsimu2::core::Direction::Left => RotationState::West,
-      crate::tetsimu2::core::Direction::Down => RotationState::South,
-      crate::tetsimu2::core::Direction::Right => RotationState::East,
-    }
-  }
-}
-
-impl From<RotationState> for crate::tetsimu2::core::Direction {
-  fn from(state: RotationState) -> Self {
-    match state {
-      RotationState::North => crate::tetsimu2::core::Direction::Up,
-      RotationState::West => crate::tetsimu2::core::Direction::Left,
-      RotationState::South => crate::tetsimu2::core::Direction::Down,
-      RotationState::East => crate::tetsimu2::core::Direction::Right,
-    }
-  }
-}
+pub mod bot_options;
+pub mod bot_session;
+pub mod candidates;
+pub mod cold_clear_backend;
+pub mod grade;
+pub mod opener_book;
+pub mod opener_catalog;
+pub mod selfplay;
+pub mod tbp_backend;
+pub mod tutor_backend;
+
+const MAX_CANDIDATES: usize = 3;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const RESEND_INTERVAL: Duration = Duration::from_millis(1000);
 
 pub struct TutorProcessor {
   out: ws::Sender,
@@ -91,13 +83,26 @@ pub struct TutorProcessor {
   settings: Arc<Settings>,
   status: Mutex<Tetsimu2Status>,
   is_done: AtomicBool,
-  tutor_if: Mutex<cold_clear::Interface>,
+  backend: Mutex<Option<Box<dyn TutorBackend>>>,
+  session: Mutex<Option<BotSession>>,
+  options: cold_clear::Options,
+  evaluator: Standard,
+  score_source: ScoreSource,
+  budget: ThinkBudget,
+  mode: TutorModeOption,
+  opener: Option<OpenerOption>,
+  init_error: Option<String>,
 }
 
 struct Tetsimu2Status {
   status_id: String,
   prev_steps: Vec<Step>,
+  sent_steps: Vec<Step>,
   last_sent_time: Instant,
+  thinking_since: Instant,
+  is_final_sent: bool,
+  suggestion: Option<Placement>,
+  openers: Vec<OpenerProgress>,
 }
 
 impl Default for Tetsimu2Status {
@@ -105,7 +110,12 @@ impl Default for Tetsimu2Status {
     Tetsimu2Status {
       status_id: String::default(),
       prev_steps: Vec::default(),
+      sent_steps: Vec::default(),
       last_sent_time: Instant::now(),
+      thinking_since: Instant::now(),
+      is_final_sent: false,
+      suggestion: None,
+      openers: Vec::default(),
     }
   }
 }
@@ -150,77 +160,82 @@ impl TutorProcessor {
     info!("TutorProcessor is ready.");
 
     while !self.is_done.load(Ordering::Relaxed) {
-      std::thread::sleep(Duration::from_millis(100));
+      std::thread::sleep(POLL_INTERVAL);
 
       let mut status = self.status.lock().unwrap();
-      let request_message_id = status.status_id.clone();
-
-      let tutor_if = self.tutor_if.lock().unwrap();
-
-      tutor_if.suggest_next_move(0);
-      let poll_result = tutor_if.poll_next_move();
-      if let Err(_) = poll_result {
+      if status.is_final_sent {
         continue;
       }
-      let (_, info) = poll_result.ok().unwrap();
+      let request_message_id = status.status_id.clone();
 
-      let plan = match info {
-        cold_clear::Info::Normal(info) => info.plan,
-        cold_clear::Info::PcLoop(info) => info.plan,
-        _ => {
-          continue;
-        }
+      let mut session = self.session.lock().unwrap();
+      let session = match session.as_mut() {
+        Some(x) => x,
+        None => continue,
+      };
+      let book_move = match self.mode {
+        TutorModeOption::Opener => book_move(session.state(), self.opener),
+        _ => None,
+      };
+      let (suggestion, mode, book_line) = match book_move {
+        Some(x) => (
+          Suggestion {
+            plan: vec![x.piece.clone()],
+            mv: BotMove {
+              hold: x.hold,
+              piece: x.piece,
+              is_spin: false,
+            },
+            depth: 0,
+            nodes: 0,
+            is_pc_loop: false,
+          },
+          TutorModeOption::Opener,
+          Some(x.opener),
+        ),
+        None => match session.suggest() {
+          Some(x) if x.is_pc_loop => (x, TutorModeOption::PcLoop, None),
+          Some(x) => (x, TutorModeOption::Normal, None),
+          None => continue,
+        },
       };
 
-      let steps: Vec<_> = plan
-        .into_iter()
-        .map(|(falling_piece, _)| {
-          let r#type = Tetromino::from(falling_piece.kind.0);
-          let dir = crate::tetsimu2::core::Direction::from(falling_piece.kind.1);
-          let mut x = falling_piece.x;
-          let mut y = falling_piece.y;
-          if falling_piece.kind.0 == Piece::I {
-            match falling_piece.kind.1 {
-              RotationState::West => {
-                y += 1;
-              }
-              RotationState::South => {
-                y -= 1;
-                x += 1;
-              }
-              RotationState::East => {
-                x -= 1;
-              }
-              _ => {}
-            }
-          }
-
-          Step {
-            r#type: r#type as u8,
-            dir: dir as u8,
-            x: x as i8,
-            y: y as i8,
-          }
-        })
-        .collect();
+      let steps: Vec<_> = suggestion.plan.iter().map(to_step).collect();
 
       if steps.is_empty() {
         status.prev_steps = steps;
         continue;
       }
 
-      if status.prev_steps.is_empty() || steps[0] != status.prev_steps[0] {
-        debug!("Steps changed.");
+      // Book moves never change, so they are final at once.
+      let elapsed = status.thinking_since.elapsed();
+      let is_final = book_line.is_some() || self.budget.is_reached(elapsed, suggestion.nodes);
+      if !is_final {
+        if status.prev_steps.is_empty() || steps[0] != status.prev_steps[0] {
+          debug!("Steps changed.");
 
-        status.prev_steps = steps;
-        continue;
-      }
+          status.prev_steps = steps;
+          continue;
+        }
 
-      if status.prev_steps == steps && status.last_sent_time.elapsed().as_millis() < 1000 {
-        continue;
+        if status.prev_steps == steps && status.last_sent_time.elapsed() < RESEND_INTERVAL {
+          continue;
+        }
       }
 
       status.prev_steps = steps.clone();
+      let plan_changed = status.sent_steps != steps;
+      status.sent_steps = steps.clone();
+      status.is_final_sent = is_final;
+      let nodes_per_sec = match elapsed.as_millis() {
+        0 => 0,
+        ms => (suggestion.nodes as u128 * 1000 / ms) as u32,
+      };
+      let candidates = self.candidates(session.state(), &suggestion, &steps);
+      status.suggestion = Some(Placement {
+        hold: suggestion.mv.hold,
+        piece: suggestion.mv.piece.clone(),
+      });
 
       let steps = HubMessage::Steps(StepsMessage {
         header: HubMessageHeader {
@@ -230,6 +245,16 @@ impl TutorProcessor {
         body: StepsMessageBody {
           request_message_id: request_message_id,
           steps,
+          candidates,
+          score_source: self.score_source,
+          depth: suggestion.depth,
+          nodes: suggestion.nodes,
+          nodes_per_sec,
+          elapsed_ms: elapsed.as_millis() as u32,
+          plan_changed,
+          is_final,
+          mode,
+          book_line,
         },
       });
 
@@ -247,9 +272,151 @@ impl TutorProcessor {
     self.is_done.store(true, Ordering::Relaxed);
   }
 
+  // The bot's choice with its plan and the best other placements, all ordered by score.
+  fn candidates(
+    &self,
+    state: &TutorState,
+    suggestion: &Suggestion,
+    steps: &[Step],
+  ) -> Vec<StepsCandidate> {
+    let mv = &suggestion.mv;
+    let best = &mv.piece;
+    let key = |piece: &CurrentTetromino| {
+      let mut blocks = piece.blocks();
+      blocks.sort();
+      (piece.r#type, blocks)
+    };
+    let best_key = key(best);
+
+    let drop_type = match self.options.mode {
+      MovementMode::HardDropOnly => DropType::HardDrop,
+      _ => DropType::SoftDrop,
+    };
+    let others = candidates(state, drop_type, &self.evaluator)
+      .into_iter()
+      .filter(|x| key(&x.piece) != best_key)
+      .take(MAX_CANDIDATES - 1)
+      .map(|x| StepsCandidate {
+        hold: x.hold,
+        score: x.score,
+        is_bot_choice: false,
+        steps: vec![to_step(&x.piece)],
+        depth: 0,
+        nodes: 0,
+      });
+
+    let mut candidates: Vec<_> = std::iter::once(StepsCandidate {
+      hold: mv.hold,
+      score: score(state, best, &self.evaluator),
+      is_bot_choice: true,
+      steps: steps.to_vec(),
+      depth: suggestion.depth,
+      nodes: suggestion.nodes,
+    })
+    .chain(others)
+    .collect();
+    candidates.sort_by_key(|x| std::cmp::Reverse(x.score));
+    candidates
+  }
+
+  fn send_grade(
+    &self,
+    request_message_id: &str,
+    prev: &TutorState,
+    next: &TutorState,
+    suggested: &Placement,
+  ) {
+    let played = match infer_placement(prev, next) {
+      Some(x) => x,
+      None => {
+        debug!("Could not infer the placement.");
+        return;
+      }
+    };
+
+    let grade = grade(prev, &played, suggested, &self.evaluator);
+    let message = HubMessage::Grade(GradeMessage {
+      header: HubMessageHeader {
+        version: String::from(VERSION),
+        message_id: Uuid::new_v4().to_string(),
+      },
+      body: GradeMessageBody {
+        request_message_id: String::from(request_message_id),
+        verdict: grade.verdict,
+        delta: grade.delta,
+        score_source: self.score_source,
+        played: GradePlacement {
+          hold: played.hold,
+          score: grade.played_score,
+          step: to_step(&played.piece),
+        },
+        suggested: GradePlacement {
+          hold: suggested.hold,
+          score: grade.suggested_score,
+          step: to_step(&suggested.piece),
+        },
+      },
+    });
+
+    let json = serde_json::to_string(&message).unwrap();
+    debug!("response:\n{}", json);
+    if let Err(e) = self.out.send(json) {
+      error!("{}", e);
+    }
+  }
+
+  fn send_openers(
+    &self,
+    request_message_id: &str,
+    openers: &[OpenerProgress],
+    deviated_from: Vec<OpenerOption>,
+  ) {
+    let message = HubMessage::Opener(OpenerMessage {
+      header: HubMessageHeader {
+        version: String::from(VERSION),
+        message_id: Uuid::new_v4().to_string(),
+      },
+      body: OpenerMessageBody {
+        request_message_id: String::from(request_message_id),
+        candidates: openers
+          .iter()
+          .map(|x| OpenerCandidate {
+            opener: x.opener,
+            variant: x.variant.clone(),
+            placed: x.placed as u32,
+            total: x.total as u32,
+            is_possible: x.is_possible,
+          })
+          .collect(),
+        deviated_from,
+      },
+    });
+
+    let json = serde_json::to_string(&message).unwrap();
+    debug!("response:\n{}", json);
+    if let Err(e) = self.out.send(json) {
+      error!("{}", e);
+    }
+  }
+
   fn initialize(&self, message: &InitTutorMessageReq) {
     info!("Initializing.");
 
+    let body = match &self.init_error {
+      None => InitTutorMessageResBody {
+        succeeded: true,
+        message: String::from(""),
+      },
+      Some(e) => {
+        error!("{}", e);
+        self.is_done.store(true, Ordering::Relaxed);
+        InitTutorMessageResBody {
+          succeeded: false,
+          message: e.clone(),
+        }
+      }
+    };
+
     let response = HubMessage::InitTutor(InitTutorMessageRes {
       header: HubMessageResHeader {
         version: String::from(VERSION),
@@ -257,7 +424,7 @@ impl TutorProcessor {
         request_message_id: message.header.message_id.clone(),
         result: InitTutorMessageResResult::Succeeded as i32,
       },
-      body: InitTutorMessageResBody {},
+      body,
     });
 
     let json = serde_json::to_string(&response).unwrap();
@@ -294,56 +461,48 @@ impl TutorProcessor {
     let mut status = self.status.lock().unwrap();
     status.status_id = message.header.message_id.clone();
 
-    if !message.body.can_hold {
-      // This is because cold clear is not supported hold only movement
-      return;
-    }
+    let state = match TutorState::from_status(&message.body) {
+      Ok(x) => x,
+      Err(e) => {
+        error!("{}", e);
+        return;
+      }
+    };
 
     status.prev_steps = vec![];
-
-    let mut field = [[false; 10]; 40];
-    for cell in 0..message.body.field.len() {
-      let x = cell % MAX_FIELD_WIDTH as usize;
-      let y = cell / MAX_FIELD_WIDTH as usize;
-      field[y][x] = message.body.field[cell as usize] != FieldCellValue::None as u8;
+    status.sent_steps = vec![];
+    status.thinking_since = Instant::now();
+    status.is_final_sent = false;
+
+    let openers = recognize(&state);
+    let deviated = deviated_from(&status.openers, &state);
+    if !openers.is_empty() || !deviated.is_empty() {
+      self.send_openers(&status.status_id, &openers, deviated);
     }
+    status.openers = openers;
 
-    let hold_type: Option<Tetromino> = FromPrimitive::from_u8(message.body.hold_type);
-    let hold = match hold_type {
-      Some(t) => Some(Piece::from(t)),
-      _ => None,
-    };
+    let mut session = self.session.lock().unwrap();
+    if let (Some(session), Some(suggested)) = (session.as_ref(), status.suggestion.take()) {
+      self.send_grade(&status.status_id, session.state(), &state, &suggested);
+    }
 
-    let combo = (message.body.ren + 1) as u32;
-    let is_btb = message.body.is_btb;
-    let board = Board::new_with_state(field, EnumSet::all(), hold, is_btb, combo).into();
-    let tutor_if = cold_clear::Interface::launch(
-      board,
-      cold_clear::Options {
-        speculate: false,
-        ..Default::default()
-      },
-      cold_clear::evaluation::Standard::default(),
-      None,
-    );
-
-    let nexts: Vec<_> = message
-      .body
-      .nexts
-      .chars()
-      .map(|c| match Tetromino::try_from(c) {
-        Ok(r#type) => Piece::from(r#type),
-        _ => panic!("Cannot convert '{}' into Piece", c),
-      })
-      .collect();
-
-    for next in nexts {
-      tutor_if.add_next_piece(next);
+    match session.as_mut() {
+      Some(session) => session.update(state),
+      None => {
+        if let Some(backend) = self.backend.lock().unwrap().take() {
+          *session = Some(BotSession::start(backend, state));
+        }
+      }
     }
-    tutor_if.suggest_next_move(0);
+  }
+}
 
-    let mut prc_tutor_if = self.tutor_if.lock().unwrap();
-    *prc_tutor_if = tutor_if;
+fn to_step(piece: &CurrentTetromino) -> Step {
+  Step {
+    r#type: piece.r#type as u8,
+    dir: piece.direction as u8,
+    x: piece.x as i8,
+    y: piece.y as i8,
   }
 }
 
@@ -352,20 +511,39 @@ pub fn execute(
   message: InitTutorMessageReq,
   settings: &Arc<Settings>,
 ) -> Arc<TutorProcessor> {
+  let tutor_options = settings.tutor.merge(&message.body.options);
+  debug!("tutor options: {:?}", tutor_options);
+  let options = cold_clear_options(&tutor_options);
+  let kind = tutor_options.backend.unwrap_or(TutorBackendKind::ColdClear);
+  let (evaluator, backend) = match evaluator(&tutor_options) {
+    Ok(x) => {
+      let backend = create_backend(settings, kind, &options, &x);
+      (x, backend)
+    }
+    Err(e) => (Standard::default(), Err(e)),
+  };
+  let (backend, init_error) = match backend {
+    Ok(x) => (Some(x), None),
+    Err(e) => (None, Some(e)),
+  };
+
   let processor = Arc::new(TutorProcessor {
     out: out.clone(),
     settings: settings.clone(),
     status: Mutex::new(Tetsimu2Status::default()),
     is_done: AtomicBool::from(false),
-    tutor_if: Mutex::new(cold_clear::Interface::launch(
-      Board::new(),
-      cold_clear::Options {
-        speculate: false,
-        ..Default::default()
-      },
-      cold_clear::evaluation::Standard::default(),
-      None,
-    )),
+    backend: Mutex::new(backend),
+    session: Mutex::new(None),
+    options,
+    evaluator,
+    score_source: match kind {
+      TutorBackendKind::ColdClear => ScoreSource::BotEval,
+      TutorBackendKind::Tbp => ScoreSource::HubStaticEval,
+    },
+    budget: think_budget(&tutor_options),
+    mode: tutor_options.mode.unwrap_or(TutorModeOption::Normal),
+    opener: tutor_options.opener,
+    init_error,
   });
 
   let processor2 = Arc::clone(&processor);
@@ -0,0 +1,286 @@
+use crate::settings::MovementModeOption;
+use crate::settings::PcLoopOption;
+use crate::settings::SpawnRuleOption;
+use crate::settings::TutorModeOption;
+use crate::settings::TutorOptions;
+use crate::settings::WeightsPreset;
+use cold_clear::evaluation::Standard;
+use cold_clear::moves::MovementMode;
+use cold_clear::PcPriority;
+use libtetris::SpawnRule;
+use std::time::Duration;
+
+// How long the bot may think about one piece before its suggestion is final. Without either
+// limit suggestions are never final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ThinkBudget {
+  pub time: Option<Duration>,
+  pub nodes: Option<u32>,
+}
+
+impl ThinkBudget {
+  pub fn is_reached(&self, elapsed: Duration, nodes: u32) -> bool {
+    let is_time_up = match self.time {
+      Some(time) => elapsed >= time,
+      None => false,
+    };
+    let is_nodes_reached = match self.nodes {
+      Some(x) => nodes >= x,
+      None => false,
+    };
+
+    is_time_up || is_nodes_reached
+  }
+}
+
+pub fn cold_clear_options(options: &TutorOptions) -> cold_clear::Options {
+  let mut cc_options = cold_clear::Options {
+    speculate: false,
+    ..Default::default()
+  };
+
+  if let Some(threads) = options.threads {
+    cc_options.threads = threads.max(1);
+  }
+  if let Some(min_nodes) = options.min_nodes {
+    cc_options.min_nodes = min_nodes;
+  }
+  if let Some(max_nodes) = options.max_nodes {
+    cc_options.max_nodes = max_nodes.max(cc_options.min_nodes);
+  }
+  // The search itself ends at the node budget, not only the suggestion.
+  if let Some(think_nodes) = options.think_nodes {
+    cc_options.max_nodes = cc_options.max_nodes.min(think_nodes.max(1));
+    cc_options.min_nodes = cc_options.min_nodes.min(cc_options.max_nodes);
+  }
+  if let Some(pc_loop) = options.pc_loop {
+    cc_options.pcloop = match pc_loop {
+      PcLoopOption::Off => None,
+      PcLoopOption::Fastest => Some(PcPriority::Fastest),
+      PcLoopOption::HighestAttack => Some(PcPriority::HighestAttack),
+    };
+  }
+  if options.mode == Some(TutorModeOption::PcLoop) && cc_options.pcloop.is_none() {
+    cc_options.pcloop = Some(PcPriority::Fastest);
+  }
+  if let Some(spawn_rule) = options.spawn_rule {
+    cc_options.spawn_rule = match spawn_rule {
+      SpawnRuleOption::Row19Or20 => SpawnRule::Row19Or20,
+      SpawnRuleOption::Row21AndFall => SpawnRule::Row21AndFall,
+    };
+  }
+  if let Some(mode) = options.movement_mode {
+    cc_options.mode = match mode {
+      MovementModeOption::ZeroG => MovementMode::ZeroG,
+      MovementModeOption::ZeroGComplete => MovementMode::ZeroGComplete,
+      MovementModeOption::TwentyG => MovementMode::TwentyG,
+      MovementModeOption::HardDropOnly => MovementMode::HardDropOnly,
+    };
+  }
+
+  cc_options
+}
+
+// Cold clear cannot be told that hold is locked only for the current piece, so the whole search
+// runs without hold until the next status arrives.
+pub fn status_options(options: &cold_clear::Options, can_hold: bool) -> cold_clear::Options {
+  cold_clear::Options {
+    use_hold: options.use_hold && can_hold,
+    ..options.clone()
+  }
+}
+
+pub fn think_budget(options: &TutorOptions) -> ThinkBudget {
+  ThinkBudget {
+    time: options.think_ms.map(|ms| Duration::from_millis(ms as u64)),
+    nodes: options.think_nodes,
+  }
+}
+
+pub fn evaluator(options: &TutorOptions) -> Result<Standard, String> {
+  let preset = preset(options.weights_preset.unwrap_or(WeightsPreset::Standard));
+  let weights = match &options.weights {
+    Some(x) if !x.is_empty() => x,
+    _ => return Ok(preset),
+  };
+
+  let mut value = serde_json::to_value(&preset).map_err(|e| e.to_string())?;
+  let fields = value.as_object_mut().unwrap();
+  for (key, weight) in weights {
+    if !fields.contains_key(key) {
+      return Err(format!("Unknown evaluator weight '{}'.", key));
+    }
+    fields.insert(key.clone(), weight.clone());
+  }
+
+  serde_json::from_value(value).map_err(|e| format!("Invalid evaluator weights. {}", e))
+}
+
+fn preset(preset: WeightsPreset) -> Standard {
+  match preset {
+    WeightsPreset::Standard => Standard::default(),
+    WeightsPreset::Fast => Standard::fast_config(),
+    WeightsPreset::Downstack => {
+      let standard = Standard::default();
+      Standard {
+        height: standard.height * 2,
+        top_half: standard.top_half * 2,
+        top_quarter: standard.top_quarter * 2,
+        cavity_cells: standard.cavity_cells * 2,
+        overhang_cells: standard.overhang_cells * 2,
+        covered_cells: standard.covered_cells * 2,
+        tslot: [0; 4],
+        tspin1: standard.tspin1 / 2,
+        tspin2: standard.tspin2 / 2,
+        tspin3: standard.tspin3 / 2,
+        back_to_back: standard.back_to_back / 2,
+        clear1: 0,
+        clear2: 0,
+        clear3: 0,
+        wasted_t: 0,
+        ..standard
+      }
+    }
+    WeightsPreset::Tspin => {
+      let standard = Standard::default();
+      Standard {
+        tslot: [
+          standard.tslot[0] * 2,
+          standard.tslot[1] * 2,
+          standard.tslot[2] * 2,
+          standard.tslot[3] * 2,
+        ],
+        tspin2: standard.tspin2 * 2,
+        tspin3: standard.tspin3 * 2,
+        back_to_back: standard.back_to_back * 2,
+        b2b_clear: standard.b2b_clear * 2,
+        wasted_t: standard.wasted_t * 2,
+        ..standard
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn weights(value: serde_json::Value) -> Option<serde_json::Map<String, serde_json::Value>> {
+    value.as_object().cloned()
+  }
+
+  #[test]
+  fn cold_clear_options() {
+    let actual = super::cold_clear_options(&TutorOptions::default());
+    assert_eq!(actual.speculate, false);
+    assert_eq!(actual.pcloop, None);
+
+    let actual = super::cold_clear_options(&TutorOptions {
+      threads: Some(4),
+      min_nodes: Some(1000),
+      max_nodes: Some(100),
+      pc_loop: Some(PcLoopOption::HighestAttack),
+      spawn_rule: Some(SpawnRuleOption::Row21AndFall),
+      movement_mode: Some(MovementModeOption::HardDropOnly),
+      ..Default::default()
+    });
+    assert_eq!(actual.threads, 4);
+    assert_eq!(actual.min_nodes, 1000);
+    assert_eq!(actual.max_nodes, 1000);
+    assert_eq!(actual.pcloop, Some(PcPriority::HighestAttack));
+    assert_eq!(actual.spawn_rule, SpawnRule::Row21AndFall);
+    assert_eq!(actual.mode, MovementMode::HardDropOnly);
+
+    let actual = super::cold_clear_options(&TutorOptions {
+      mode: Some(TutorModeOption::PcLoop),
+      ..Default::default()
+    });
+    assert_eq!(actual.pcloop, Some(PcPriority::Fastest));
+
+    let actual = super::cold_clear_options(&TutorOptions {
+      mode: Some(TutorModeOption::PcLoop),
+      pc_loop: Some(PcLoopOption::HighestAttack),
+      ..Default::default()
+    });
+    assert_eq!(actual.pcloop, Some(PcPriority::HighestAttack));
+
+    let actual = super::cold_clear_options(&TutorOptions {
+      min_nodes: Some(1000),
+      max_nodes: Some(100000),
+      think_nodes: Some(500),
+      ..Default::default()
+    });
+    assert_eq!(actual.min_nodes, 500);
+    assert_eq!(actual.max_nodes, 500);
+  }
+
+  #[test]
+  fn status_options() {
+    let options = super::cold_clear_options(&TutorOptions::default());
+    assert_eq!(super::status_options(&options, true).use_hold, true);
+    assert_eq!(super::status_options(&options, false).use_hold, false);
+  }
+
+  #[test]
+  fn think_budget() {
+    let budget = super::think_budget(&TutorOptions::default());
+    assert_eq!(budget.is_reached(Duration::from_secs(60), u32::MAX), false);
+
+    let budget = super::think_budget(&TutorOptions {
+      think_ms: Some(500),
+      think_nodes: Some(10000),
+      ..Default::default()
+    });
+    assert_eq!(budget.is_reached(Duration::from_millis(499), 9999), false);
+    assert_eq!(budget.is_reached(Duration::from_millis(500), 0), true);
+    assert_eq!(budget.is_reached(Duration::from_millis(0), 10000), true);
+  }
+
+  #[test]
+  fn evaluator_presets() {
+    let standard = Standard::default();
+    assert_eq!(evaluator(&TutorOptions::default()), Ok(standard.clone()));
+
+    let downstack = evaluator(&TutorOptions {
+      weights_preset: Some(WeightsPreset::Downstack),
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(downstack.tslot, [0; 4]);
+    assert!(downstack.height < standard.height);
+
+    let tspin = evaluator(&TutorOptions {
+      weights_preset: Some(WeightsPreset::Tspin),
+      ..Default::default()
+    })
+    .unwrap();
+    assert!(tspin.tspin2 > standard.tspin2);
+  }
+
+  #[test]
+  fn evaluator_weights() {
+    let actual = evaluator(&TutorOptions {
+      weights_preset: Some(WeightsPreset::Tspin),
+      weights: weights(json!({ "height": -80, "tslot": [1, 2, 3, 4] })),
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(actual.height, -80);
+    assert_eq!(actual.tslot, [1, 2, 3, 4]);
+    assert_eq!(actual.tspin2, Standard::default().tspin2 * 2);
+
+    assert_eq!(
+      evaluator(&TutorOptions {
+        weights: weights(json!({ "heigth": -80 })),
+        ..Default::default()
+      }),
+      Err(String::from("Unknown evaluator weight 'heigth'."))
+    );
+    assert!(evaluator(&TutorOptions {
+      weights: weights(json!({ "height": "high" })),
+      ..Default::default()
+    })
+    .is_err());
+  }
+}
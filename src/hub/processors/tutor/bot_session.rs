@@ -0,0 +1,303 @@
+use crate::hub::messages::tetsimu2::notify_status::NotifyStatusMessageReqBody;
+use crate::hub::processors::tutor::tutor_backend::BotMove;
+use crate::hub::processors::tutor::tutor_backend::Suggestion;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::placements::PlayState;
+use log::{debug, error, warn};
+use num_traits::FromPrimitive;
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TutorState {
+  pub field: Field,
+  pub queue: Vec<Tetromino>,
+  pub hold: Option<Tetromino>,
+  pub combo: u32,
+  pub is_btb: bool,
+  pub can_hold: bool,
+}
+
+impl TutorState {
+  pub fn from_status(body: &NotifyStatusMessageReqBody) -> Result<Self, String> {
+    let mut field = Field::new();
+    for (i, &cell) in body.field.iter().enumerate() {
+      let x = i as i32 % MAX_FIELD_WIDTH;
+      let y = i as i32 / MAX_FIELD_WIDTH;
+      let value = FromPrimitive::from_u8(cell).unwrap_or(FieldCellValue::Garbage);
+      field.set_cell(x, y, value);
+    }
+
+    let queue = body
+      .nexts
+      .chars()
+      .map(|c| Tetromino::try_from(c).map_err(|_| format!("Cannot convert '{}' into Piece", c)))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TutorState {
+      field,
+      queue,
+      hold: FromPrimitive::from_u8(body.hold_type),
+      combo: (body.ren + 1) as u32,
+      is_btb: body.is_btb,
+      can_hold: body.can_hold,
+    })
+  }
+
+  pub fn play_state(&self) -> PlayState<'_> {
+    PlayState {
+      field: &self.field,
+      queue: &self.queue,
+      hold: self.hold,
+      can_hold: self.can_hold,
+    }
+  }
+
+  // The state the bot expects after `mv` is played. Back-to-back follows the spin the bot
+  // detected for the move.
+  pub fn after(&self, mv: &BotMove) -> Option<TutorState> {
+    self.placed(mv.hold, &mv.piece, mv.is_spin)
+  }
+
+  pub fn placed(
+    &self,
+    use_hold: bool,
+    piece: &CurrentTetromino,
+    is_spin: bool,
+  ) -> Option<TutorState> {
+    let mut queue = self.queue.clone();
+    let mut hold = self.hold;
+    if use_hold {
+      if queue.is_empty() {
+        return None;
+      }
+      let current = queue.remove(0);
+      if let Some(held) = hold.replace(current) {
+        queue.insert(0, held);
+      }
+    }
+
+    if queue.is_empty() {
+      return None;
+    }
+    if queue.remove(0) != piece.r#type {
+      return None;
+    }
+
+    let mut field = self.field.clone();
+    field.settle_tetromino(piece);
+    let cleared_lines = field.clear_lines();
+
+    let (combo, is_btb) = if cleared_lines == 0 {
+      (0, self.is_btb)
+    } else {
+      let is_btb = cleared_lines == 4 || is_spin;
+      (self.combo + 1, is_btb)
+    };
+
+    Some(TutorState {
+      field,
+      queue,
+      hold,
+      combo,
+      is_btb,
+      can_hold: self.can_hold,
+    })
+  }
+
+  // Compares the cells, hold and known pieces, ignoring colours and pieces appended to the queue.
+  pub fn is_followed_by(&self, next: &TutorState) -> bool {
+    let same_field = (0..MAX_FIELD_HEIGHT).all(|y| {
+      (0..MAX_FIELD_WIDTH).all(|x| {
+        (self.field.get_cell(x, y) == FieldCellValue::None)
+          == (next.field.get_cell(x, y) == FieldCellValue::None)
+      })
+    });
+
+    same_field && self.hold == next.hold && next.queue.starts_with(&self.queue)
+  }
+
+  // Returns the pieces appended to the queue when `next` only differs from this state by them.
+  pub fn continued_by(&self, next: &TutorState) -> Option<Vec<Tetromino>> {
+    if !self.is_followed_by(next)
+      || self.combo != next.combo
+      || self.is_btb != next.is_btb
+      || self.can_hold != next.can_hold
+    {
+      return None;
+    }
+
+    Some(next.queue[self.queue.len()..].to_vec())
+  }
+}
+
+pub struct BotSession {
+  backend: Box<dyn TutorBackend>,
+  state: TutorState,
+  last_move: Option<BotMove>,
+}
+
+impl BotSession {
+  pub fn start(mut backend: Box<dyn TutorBackend>, state: TutorState) -> Self {
+    if let Err(e) = backend.start(&state) {
+      error!("{}", e);
+    }
+
+    BotSession {
+      backend,
+      state,
+      last_move: None,
+    }
+  }
+
+  // Keeps the search tree when the player played the last suggestion, and restarts the bot on
+  // anything else such as garbage, undo or edits.
+  pub fn update(&mut self, state: TutorState) {
+    let added = self
+      .last_move
+      .as_ref()
+      .and_then(|mv| self.state.after(mv))
+      .and_then(|predicted| predicted.continued_by(&state));
+
+    let is_continued = match (self.last_move.take(), added) {
+      (Some(mv), Some(added)) => {
+        debug!("Continue bot with {:?}.", added);
+        let result = self
+          .backend
+          .play(&mv)
+          .and_then(|_| self.backend.add_pieces(&added));
+        if let Err(e) = &result {
+          warn!("{}", e);
+        }
+        result.is_ok()
+      }
+      _ => false,
+    };
+
+    if !is_continued {
+      if let Err(e) = self.backend.start(&state) {
+        error!("{}", e);
+      }
+    }
+    self.state = state;
+  }
+
+  pub fn state(&self) -> &TutorState {
+    &self.state
+  }
+
+  pub fn suggest(&mut self) -> Option<Suggestion> {
+    let suggestion = self.backend.suggest()?;
+    self.last_move = Some(suggestion.mv.clone());
+    Some(suggestion)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::Direction;
+
+  fn state(rows: &[&str], queue: &str, hold: Option<Tetromino>) -> TutorState {
+    TutorState {
+      field: make_field(&rows.concat()),
+      queue: queue
+        .chars()
+        .map(|c| Tetromino::try_from(c).unwrap())
+        .collect(),
+      hold,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    }
+  }
+
+  fn mv(r#type: Tetromino, direction: Direction, x: i32, y: i32, hold: bool) -> BotMove {
+    BotMove {
+      hold,
+      piece: CurrentTetromino {
+        r#type,
+        direction,
+        x,
+        y,
+      },
+      is_spin: false,
+    }
+  }
+
+  #[test]
+  fn after_should_place_piece() {
+    #[rustfmt::skip]
+    let current = state(&[
+      "GGGGGGNNGG",
+    ], "OTI", None);
+    let actual = current.after(&mv(Tetromino::O, Direction::Up, 6, 0, false));
+
+    #[rustfmt::skip]
+    let expected = TutorState {
+      combo: 1,
+      ..state(&[
+        "NNNNNNOONN",
+      ], "TI", None)
+    };
+    assert_eq!(actual, Some(expected));
+  }
+
+  #[test]
+  fn after_should_hold() {
+    let current = state(&[], "OTI", None);
+    let actual = current
+      .after(&mv(Tetromino::T, Direction::Up, 4, 0, true))
+      .unwrap();
+    assert_eq!(actual.hold, Some(Tetromino::O));
+    assert_eq!(actual.queue, vec![Tetromino::I]);
+
+    let current = state(&[], "OTI", Some(Tetromino::S));
+    let actual = current
+      .after(&mv(Tetromino::S, Direction::Up, 4, 0, true))
+      .unwrap();
+    assert_eq!(actual.hold, Some(Tetromino::O));
+    assert_eq!(actual.queue, vec![Tetromino::T, Tetromino::I]);
+
+    assert_eq!(
+      current.after(&mv(Tetromino::T, Direction::Up, 4, 0, true)),
+      None
+    );
+  }
+
+  #[test]
+  fn continued_by() {
+    #[rustfmt::skip]
+    let predicted = state(&[
+      "NNNNNNOONN",
+    ], "TI", None);
+
+    #[rustfmt::skip]
+    let next = state(&[
+      "NNNNNNGGNN",
+    ], "TIZS", None);
+    assert_eq!(
+      predicted.continued_by(&next),
+      Some(vec![Tetromino::Z, Tetromino::S])
+    );
+
+    #[rustfmt::skip]
+    let garbage = state(&[
+      "NNNNNNOONN",
+      "GGGGGNGGGG",
+    ], "TIZ", None);
+    assert_eq!(predicted.continued_by(&garbage), None);
+
+    #[rustfmt::skip]
+    let undone = state(&[
+      "NNNNNNOONN",
+    ], "OTI", None);
+    assert_eq!(predicted.continued_by(&undone), None);
+  }
+}
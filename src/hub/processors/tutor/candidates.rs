@@ -0,0 +1,196 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::analyze_pc::solution_steps::spawn;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::cold_clear_backend::board;
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::placements::placements;
+use crate::tetsimu2::router::RouteSearcher;
+use cold_clear::evaluation::Evaluator;
+use cold_clear::evaluation::Standard;
+use libtetris::FallingPiece;
+use libtetris::Piece;
+use libtetris::PieceState;
+use libtetris::RotationState;
+use libtetris::TspinStatus;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+  pub hold: bool,
+  pub piece: CurrentTetromino,
+  pub score: i32,
+}
+
+// Every reachable placement of the current piece, and of the piece hold would bring in, ordered
+// from the best score.
+pub fn candidates(state: &TutorState, drop_type: DropType, evaluator: &Standard) -> Vec<Candidate> {
+  let current = match state.queue.first() {
+    Some(&x) => x,
+    None => return vec![],
+  };
+
+  let mut pieces = vec![(false, current)];
+  if state.can_hold {
+    if let Some(x) = state.hold.or_else(|| state.queue.get(1).copied()) {
+      if x != current {
+        pieces.push((true, x));
+      }
+    }
+  }
+
+  let mut candidates: Vec<_> = pieces
+    .into_iter()
+    .flat_map(|(hold, r#type)| {
+      reachable_placements(&state.field, r#type, drop_type)
+        .into_iter()
+        .map(move |piece| (hold, piece))
+    })
+    .map(|(hold, piece)| Candidate {
+      hold,
+      score: score(state, &piece, evaluator),
+      piece,
+    })
+    .collect();
+
+  candidates.sort_by_key(|x| std::cmp::Reverse(x.score));
+  candidates
+}
+
+fn reachable_placements(
+  field: &Field,
+  r#type: Tetromino,
+  drop_type: DropType,
+) -> Vec<CurrentTetromino> {
+  let route_searcher = RouteSearcher { field };
+  placements(field, r#type, MAX_FIELD_HEIGHT)
+    .into_iter()
+    .filter(|piece| {
+      route_searcher
+        .search_route_with_drop_type(spawn(r#type, field), piece, drop_type)
+        .is_some()
+    })
+    .collect()
+}
+
+// The bot's own evaluation of the board right after the placement. Unlike the bot's search it
+// does not look ahead, so it can rank placements differently from the bot's choice.
+pub fn score(state: &TutorState, piece: &CurrentTetromino, evaluator: &Standard) -> i32 {
+  let placement = Placement::from_current_tetromino(piece)
+    .convert(CoordinateSystem::Tetsimu2, CoordinateSystem::Libtetris);
+  let mut board = board(state);
+  let lock = board.lock_piece(FallingPiece {
+    kind: PieceState(
+      Piece::from(piece.r#type),
+      RotationState::from(piece.direction),
+    ),
+    x: placement.x,
+    y: placement.y,
+    tspin: tspin_status(&state.field, piece),
+  });
+
+  let (value, reward) = evaluator.evaluate(&lock, &board, 0, Piece::from(piece.r#type));
+  value.value + reward.value
+}
+
+// The placement alone does not tell how the piece got there, so three filled corners count as a
+// T-spin.
+fn tspin_status(field: &Field, piece: &CurrentTetromino) -> TspinStatus {
+  if piece.r#type != Tetromino::T {
+    return TspinStatus::None;
+  }
+
+  let filled_corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+    .iter()
+    .filter(|(dx, dy)| field.get_cell(piece.x + dx, piece.y + dy) != FieldCellValue::None)
+    .count();
+  if filled_corners >= 3 {
+    TspinStatus::Full
+  } else {
+    TspinStatus::None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::Direction;
+
+  fn state(rows: &[&str], queue: &[Tetromino], hold: Option<Tetromino>) -> TutorState {
+    TutorState {
+      field: make_field(&rows.concat()),
+      queue: queue.to_vec(),
+      hold,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    }
+  }
+
+  #[test]
+  fn candidates_should_prefer_line_clear() {
+    #[rustfmt::skip]
+    let state = state(&[
+      "GGGGGGGGGN",
+      "GGGGGGGGGN",
+      "GGGGGGGGGN",
+      "GGGGGGGGGN",
+    ], &[Tetromino::I, Tetromino::O], None);
+    let actual = candidates(&state, DropType::SoftDrop, &Standard::default());
+
+    assert_eq!(
+      actual[0],
+      Candidate {
+        hold: false,
+        piece: CurrentTetromino {
+          r#type: Tetromino::I,
+          direction: Direction::Right,
+          x: 8,
+          y: 2,
+        },
+        score: score(&state, &actual[0].piece, &Standard::default()),
+      }
+    );
+    assert!(actual
+      .iter()
+      .any(|x| x.hold && x.piece.r#type == Tetromino::O));
+    assert!(actual.windows(2).all(|w| w[0].score >= w[1].score));
+  }
+
+  #[test]
+  fn candidates_should_respect_hold() {
+    let mut state = state(&[], &[Tetromino::T, Tetromino::O], Some(Tetromino::T));
+    let actual = candidates(&state, DropType::SoftDrop, &Standard::default());
+    assert!(actual.iter().all(|x| !x.hold));
+
+    state.hold = None;
+    state.can_hold = false;
+    let actual = candidates(&state, DropType::SoftDrop, &Standard::default());
+    assert!(actual.iter().all(|x| x.piece.r#type == Tetromino::T));
+    assert_eq!(actual.len(), 34);
+  }
+
+  #[test]
+  fn score_should_penalize_cavities() {
+    let state = state(&[], &[Tetromino::T], None);
+    let flat = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 1,
+      y: 0,
+    };
+    let cavity = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Down,
+      x: 1,
+      y: 1,
+    };
+    let evaluator = Standard::default();
+    assert!(score(&state, &flat, &evaluator) > score(&state, &cavity, &evaluator));
+  }
+}
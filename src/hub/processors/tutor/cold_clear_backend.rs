@@ -0,0 +1,195 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::tutor::bot_options::status_options;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::tutor_backend::BotMove;
+use crate::hub::processors::tutor::tutor_backend::Suggestion;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use cold_clear::evaluation::Standard;
+use enumset::EnumSet;
+use libtetris::*;
+use log::info;
+
+impl From<Tetromino> for Piece {
+  fn from(r#type: Tetromino) -> Self {
+    match r#type {
+      Tetromino::I => Piece::I,
+      Tetromino::J => Piece::J,
+      Tetromino::L => Piece::L,
+      Tetromino::O => Piece::O,
+      Tetromino::S => Piece::S,
+      Tetromino::T => Piece::T,
+      Tetromino::Z => Piece::Z,
+    }
+  }
+}
+
+impl From<Piece> for Tetromino {
+  fn from(piece: Piece) -> Self {
+    match piece {
+      Piece::I => Tetromino::I,
+      Piece::J => Tetromino::J,
+      Piece::L => Tetromino::L,
+      Piece::O => Tetromino::O,
+      Piece::S => Tetromino::S,
+      Piece::T => Tetromino::T,
+      Piece::Z => Tetromino::Z,
+    }
+  }
+}
+
+impl From<Direction> for RotationState {
+  fn from(dir: Direction) -> Self {
+    match dir {
+      Direction::Up => RotationState::North,
+      Direction::Left => RotationState::West,
+      Direction::Down => RotationState::South,
+      Direction::Right => RotationState::East,
+    }
+  }
+}
+
+impl From<RotationState> for Direction {
+  fn from(state: RotationState) -> Self {
+    match state {
+      RotationState::North => Direction::Up,
+      RotationState::West => Direction::Left,
+      RotationState::South => Direction::Down,
+      RotationState::East => Direction::Right,
+    }
+  }
+}
+
+pub struct ColdClearBackend {
+  options: cold_clear::Options,
+  evaluator: Standard,
+  interface: Option<cold_clear::Interface>,
+  last_move: Option<cold_clear::Move>,
+  is_waiting: bool,
+}
+
+impl ColdClearBackend {
+  pub fn new(options: &cold_clear::Options, evaluator: &Standard) -> Self {
+    ColdClearBackend {
+      options: options.clone(),
+      evaluator: evaluator.clone(),
+      interface: None,
+      last_move: None,
+      is_waiting: false,
+    }
+  }
+}
+
+impl TutorBackend for ColdClearBackend {
+  fn start(&mut self, state: &TutorState) -> Result<(), String> {
+    info!("Launch cold clear.");
+
+    let interface = cold_clear::Interface::launch(
+      board(state),
+      status_options(&self.options, state.can_hold),
+      self.evaluator.clone(),
+      None,
+    );
+    for &next in &state.queue {
+      interface.add_next_piece(Piece::from(next));
+    }
+
+    self.interface = Some(interface);
+    self.last_move = None;
+    self.is_waiting = false;
+    Ok(())
+  }
+
+  fn play(&mut self, _mv: &BotMove) -> Result<(), String> {
+    let interface = match &self.interface {
+      Some(x) => x,
+      None => return Err(String::from("Cold clear is not started.")),
+    };
+    let last_move = match self.last_move.take() {
+      Some(x) => x,
+      None => return Err(String::from("Cold clear has not suggested a move.")),
+    };
+
+    if self.is_waiting {
+      interface.block_next_move();
+      self.is_waiting = false;
+    }
+
+    interface.play_next_move(last_move.expected_location);
+    Ok(())
+  }
+
+  fn add_pieces(&mut self, pieces: &[Tetromino]) -> Result<(), String> {
+    let interface = match &self.interface {
+      Some(x) => x,
+      None => return Err(String::from("Cold clear is not started.")),
+    };
+
+    for &piece in pieces {
+      interface.add_next_piece(Piece::from(piece));
+    }
+    Ok(())
+  }
+
+  fn suggest(&mut self) -> Option<Suggestion> {
+    let interface = self.interface.as_ref()?;
+    if !self.is_waiting {
+      interface.suggest_next_move(0);
+      self.is_waiting = true;
+    }
+
+    let (mv, info) = interface.poll_next_move().ok()?;
+    self.is_waiting = false;
+
+    let (plan, depth, nodes, is_pc_loop) = match info {
+      cold_clear::Info::Normal(info) => (info.plan, info.depth, info.nodes, false),
+      cold_clear::Info::PcLoop(info) => (info.plan, info.depth, 0, true),
+      _ => (vec![], 0, 0, false),
+    };
+
+    let suggestion = Suggestion {
+      mv: BotMove {
+        hold: mv.hold,
+        piece: current_tetromino(&mv.expected_location),
+        is_spin: mv.expected_location.tspin != TspinStatus::None,
+      },
+      plan: plan
+        .iter()
+        .map(|(falling_piece, _)| current_tetromino(falling_piece))
+        .collect(),
+      depth,
+      nodes,
+      is_pc_loop,
+    };
+    self.last_move = Some(mv);
+    Some(suggestion)
+  }
+}
+
+pub fn board(state: &TutorState) -> Board {
+  let mut cells = [[false; 10]; 40];
+  for (y, row) in cells.iter_mut().enumerate().take(MAX_FIELD_HEIGHT as usize) {
+    for (x, cell) in row.iter_mut().enumerate() {
+      *cell = state.field.get_cell(x as i32, y as i32) != FieldCellValue::None;
+    }
+  }
+
+  let hold = state.hold.map(Piece::from);
+  Board::new_with_state(cells, EnumSet::all(), hold, state.is_btb, state.combo)
+}
+
+pub fn current_tetromino(falling_piece: &FallingPiece) -> CurrentTetromino {
+  Placement {
+    r#type: Tetromino::from(falling_piece.kind.0),
+    direction: Direction::from(falling_piece.kind.1),
+    x: falling_piece.x,
+    y: falling_piece.y,
+  }
+  .convert(CoordinateSystem::Libtetris, CoordinateSystem::Tetsimu2)
+  .to_current_tetromino()
+}
@@ -0,0 +1,158 @@
+use crate::hub::messages::hub::grade::GradeVerdict;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::candidates::score;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::placements;
+use cold_clear::evaluation::Standard;
+
+const GOOD_DELTA: i32 = 100;
+const INACCURACY_DELTA: i32 = 300;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+  pub hold: bool,
+  pub piece: CurrentTetromino,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grade {
+  pub verdict: GradeVerdict,
+  pub delta: i32,
+  pub played_score: i32,
+  pub suggested_score: i32,
+}
+
+pub fn infer_placement(prev: &TutorState, next: &TutorState) -> Option<Placement> {
+  placements::infer_placement(&prev.play_state(), &next.play_state()).map(|x| Placement {
+    hold: x.hold,
+    piece: x.piece,
+  })
+}
+
+pub fn grade(
+  state: &TutorState,
+  played: &Placement,
+  suggested: &Placement,
+  evaluator: &Standard,
+) -> Grade {
+  let played_score = score(state, &played.piece, evaluator);
+  let suggested_score = score(state, &suggested.piece, evaluator);
+  let delta = suggested_score - played_score;
+
+  // The bot searches further ahead than the scores, so only its own choice is the best, even
+  // when another placement scores higher.
+  let verdict = if is_same(played, suggested) {
+    GradeVerdict::Best
+  } else if delta < GOOD_DELTA {
+    GradeVerdict::Good
+  } else if delta < INACCURACY_DELTA {
+    GradeVerdict::Inaccuracy
+  } else {
+    GradeVerdict::Mistake
+  };
+
+  Grade {
+    verdict,
+    delta,
+    played_score,
+    suggested_score,
+  }
+}
+
+fn is_same(a: &Placement, b: &Placement) -> bool {
+  let blocks = |piece: &CurrentTetromino| {
+    let mut blocks = piece.blocks();
+    blocks.sort();
+    blocks
+  };
+
+  a.piece.r#type == b.piece.r#type && blocks(&a.piece) == blocks(&b.piece)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::Direction;
+  use crate::tetsimu2::core::Tetromino;
+
+  fn state(rows: &[&str], queue: &[Tetromino], hold: Option<Tetromino>) -> TutorState {
+    TutorState {
+      field: make_field(&rows.concat()),
+      queue: queue.to_vec(),
+      hold,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    }
+  }
+
+  fn placement(hold: bool, r#type: Tetromino, direction: Direction, x: i32, y: i32) -> Placement {
+    Placement {
+      hold,
+      piece: CurrentTetromino {
+        r#type,
+        direction,
+        x,
+        y,
+      },
+    }
+  }
+
+  #[test]
+  fn infer_placement() {
+    #[rustfmt::skip]
+    let prev = state(&[
+      "GGGGGGGNNN",
+    ], &[Tetromino::L, Tetromino::T], None);
+
+    #[rustfmt::skip]
+    let next = state(&[
+      "NNNNNNNNNL",
+    ], &[Tetromino::T, Tetromino::S], None);
+    assert_eq!(
+      super::infer_placement(&prev, &next),
+      Some(placement(false, Tetromino::L, Direction::Up, 8, 0))
+    );
+
+    #[rustfmt::skip]
+    let held = state(&[
+      "NNNNNNNNTN",
+    ], &[Tetromino::S], Some(Tetromino::L));
+    assert_eq!(
+      super::infer_placement(&prev, &held),
+      Some(placement(true, Tetromino::T, Direction::Up, 8, 0))
+    );
+
+    #[rustfmt::skip]
+    let garbage = state(&[
+      "GGGGGGGNNN",
+      "GGGGGGGGGN",
+    ], &[Tetromino::T, Tetromino::S], None);
+    assert_eq!(super::infer_placement(&prev, &garbage), None);
+  }
+
+  #[test]
+  fn grade() {
+    #[rustfmt::skip]
+    let state = state(&[
+      "GGGGGGGNNN",
+    ], &[Tetromino::L, Tetromino::T], None);
+    let evaluator = Standard::default();
+    let suggested = placement(false, Tetromino::L, Direction::Up, 8, 0);
+
+    let actual = super::grade(&state, &suggested, &suggested, &evaluator);
+    assert_eq!(actual.verdict, GradeVerdict::Best);
+    assert_eq!(actual.delta, 0);
+
+    let played = placement(false, Tetromino::L, Direction::Down, 1, 2);
+    let actual = super::grade(&state, &played, &suggested, &evaluator);
+    assert_eq!(actual.verdict, GradeVerdict::Mistake);
+    assert_eq!(actual.delta, actual.suggested_score - actual.played_score);
+
+    // Scoring higher than the bot's choice is still not the best, and the delta stays negative.
+    let actual = super::grade(&state, &suggested, &played, &evaluator);
+    assert_eq!(actual.verdict, GradeVerdict::Good);
+    assert!(actual.delta < 0);
+  }
+}
@@ -0,0 +1,114 @@
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::opener_catalog::can_drop;
+use crate::hub::processors::tutor::opener_catalog::lines;
+use crate::hub::processors::tutor::opener_catalog::split;
+use crate::hub::processors::tutor::opener_catalog::OpenerLine;
+use crate::settings::OpenerOption;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookMove {
+  pub opener: OpenerOption,
+  pub hold: bool,
+  pub piece: CurrentTetromino,
+}
+
+// The next placement of the first catalog variant whose pieces, colours included, make up the
+// whole field so far.
+pub fn book_move(state: &TutorState, opener: Option<OpenerOption>) -> Option<BookMove> {
+  lines()
+    .iter()
+    .filter(|line| opener.is_none() || opener == Some(line.opener))
+    .find_map(|line| next_move(state, line))
+}
+
+fn next_move(state: &TutorState, line: &OpenerLine) -> Option<BookMove> {
+  let (_, remaining) = split(&state.field, line)?;
+
+  let mut choices = vec![(false, *state.queue.first()?)];
+  if state.can_hold {
+    if let Some(&held) = state.hold.as_ref().or_else(|| state.queue.get(1)) {
+      choices.push((true, held));
+    }
+  }
+
+  choices.into_iter().find_map(|(hold, r#type)| {
+    remaining
+      .iter()
+      .find(|piece| piece.r#type == r#type && can_drop(&state.field, piece))
+      .map(|piece| BookMove {
+        opener: line.opener,
+        hold,
+        piece: piece.clone(),
+      })
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::Tetromino;
+  use crate::tetsimu2::field::Field;
+  use Tetromino::*;
+
+  fn state(field: Field, queue: &[Tetromino], hold: Option<Tetromino>) -> TutorState {
+    TutorState {
+      field,
+      queue: queue.to_vec(),
+      hold,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    }
+  }
+
+  #[test]
+  fn follow_opener() {
+    let openers = [
+      (OpenerOption::Tki, [I, L, O, J, Z, S, T]),
+      (OpenerOption::DtCannon, [J, L, T, S, O, Z, I]),
+      (OpenerOption::Pco, [J, L, O, S, Z, I, T]),
+      (OpenerOption::Mko, [I, L, O, S, T, Z, J]),
+      (OpenerOption::Albatross, [I, J, L, O, S, Z, T]),
+    ];
+
+    for &(opener, queue) in &openers {
+      let mut state = state(Field::new(), &queue, None);
+      for _ in 0..6 {
+        let mv = book_move(&state, Some(opener)).unwrap();
+        assert_eq!(mv.opener, opener);
+        assert_eq!(mv.hold, false);
+        state = state.placed(mv.hold, &mv.piece, false).unwrap();
+      }
+
+      let line = lines()
+        .into_iter()
+        .find(|line| line.opener == opener)
+        .unwrap();
+      let mut expected = Field::new();
+      for piece in &line.pieces {
+        expected.settle_tetromino(piece);
+      }
+      assert_eq!(state.field, expected);
+      assert_eq!(book_move(&state, Some(opener)), None);
+    }
+  }
+
+  #[test]
+  fn book_move_with_hold() {
+    let actual = book_move(&state(Field::new(), &[T, I, L], None), None).unwrap();
+    assert_eq!(actual.opener, OpenerOption::Tki);
+    assert_eq!(actual.hold, true);
+    assert_eq!(actual.piece.r#type, I);
+  }
+
+  #[test]
+  fn out_of_book() {
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "GGGGNNNNNN",
+    ].concat());
+    assert_eq!(book_move(&state(field, &[I, L, O], None), None), None);
+  }
+}
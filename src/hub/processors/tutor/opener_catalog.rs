@@ -0,0 +1,319 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::settings::OpenerOption;
+use crate::tetfu::tetfu_decoder::TetfuDecoder;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::core::XY;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+
+// First bag of each known variant, with the variant name as the comment. The piece left out is
+// meant to be held for the next bag.
+const CATALOG: [(OpenerOption, &str); 6] = [
+  (
+    OpenerOption::Tki,
+    "v115@ChQ4DeglBeBtR4h0AeglCeBtQ4g0RphlAezhg0RpJe?AAPDAURdBA",
+  ),
+  (
+    OpenerOption::Tki,
+    "v115@BhAtFehlBtR4Beg0RpglAtR4Ceg0RpglzhAeh0JeAA?POAURVSASoF5DSsjXEFnBAA",
+  ),
+  (
+    OpenerOption::DtCannon,
+    "v115@8gAtHeBtglRpEeAtg0glRpR4wwCeg0hlR4ywAeh0Je?AAPLAke88AwEjJEuXxCA",
+  ),
+  (
+    OpenerOption::Pco,
+    "v115@9gQ4zhAtDeR4RpBtDeg0Q4RpAtglDei0ilNeAAPDAQ?lqBA",
+  ),
+  (
+    OpenerOption::Mko,
+    "v115@AhAtFeRpBtCeywRpAtCeR4wwglzhAeR4ilJeAAPDAN?xqBA",
+  ),
+  (
+    OpenerOption::Albatross,
+    "v115@zgQ4IeR4CeAtDeglQ4BeBtDeglCeAti0RphlAezhg0?RpJeAAPJAhSudDUs7aETBAAA",
+  ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenerLine {
+  pub opener: OpenerOption,
+  pub variant: String,
+  pub pieces: Vec<CurrentTetromino>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenerProgress {
+  pub opener: OpenerOption,
+  pub variant: String,
+  pub placed: usize,
+  pub total: usize,
+  pub is_possible: bool,
+}
+
+pub fn lines() -> Vec<OpenerLine> {
+  let decoder = TetfuDecoder::new();
+  CATALOG
+    .iter()
+    .map(|&(opener, fumen)| {
+      let content = decoder
+        .decode(String::from(fumen))
+        .expect("Bundled fumens are valid.");
+      OpenerLine {
+        opener,
+        variant: content.comment,
+        pieces: pieces(&content.field),
+      }
+    })
+    .collect()
+}
+
+// Splits the line into the pieces already on the field and the rest, when the field is exactly
+// the placed pieces, colours included.
+pub fn split(
+  field: &Field,
+  line: &OpenerLine,
+) -> Option<(Vec<CurrentTetromino>, Vec<CurrentTetromino>)> {
+  let (placed, remaining): (Vec<_>, Vec<_>) = line.pieces.iter().cloned().partition(|piece| {
+    piece
+      .blocks()
+      .iter()
+      .all(|&(x, y)| field.get_cell(x, y) == FieldCellValue::from(piece.r#type))
+  });
+
+  let occupied = field
+    .data
+    .iter()
+    .filter(|&&cell| cell != FieldCellValue::None)
+    .count();
+  if occupied == placed.len() * 4 {
+    Some((placed, remaining))
+  } else {
+    None
+  }
+}
+
+// Every variant the player has started, with whether the queue still allows finishing it.
+pub fn recognize(state: &TutorState) -> Vec<OpenerProgress> {
+  lines()
+    .into_iter()
+    .filter_map(|line| {
+      let (placed, remaining) = split(&state.field, &line)?;
+      if placed.is_empty() {
+        return None;
+      }
+
+      Some(OpenerProgress {
+        opener: line.opener,
+        is_possible: is_possible(
+          &state.field,
+          &remaining,
+          &state.queue,
+          state.hold,
+          state.can_hold,
+        ),
+        variant: line.variant,
+        placed: placed.len(),
+        total: line.pieces.len(),
+      })
+    })
+    .collect()
+}
+
+// The openers left by the last placement, when it matches none of the variants that were being
+// built. Finished openers and an emptied field are not deviations.
+pub fn deviated_from(prev: &[OpenerProgress], next: &TutorState) -> Vec<OpenerOption> {
+  let is_empty = next
+    .field
+    .data
+    .iter()
+    .all(|&cell| cell == FieldCellValue::None);
+  if is_empty || !recognize(next).is_empty() || prev.iter().any(|x| x.placed == x.total) {
+    return vec![];
+  }
+
+  let mut openers: Vec<_> = prev.iter().map(|x| x.opener).collect();
+  openers.dedup();
+  openers
+}
+
+// Whether the rest of the line can still be built from the queue, holding the pieces it does not
+// use. Pieces after the queue are unknown, so running out of them does not rule it out.
+fn is_possible(
+  field: &Field,
+  remaining: &[CurrentTetromino],
+  queue: &[Tetromino],
+  hold: Option<Tetromino>,
+  can_hold: bool,
+) -> bool {
+  if remaining.is_empty() {
+    return true;
+  }
+  let (&current, rest) = match queue.split_first() {
+    Some(x) => x,
+    None => return true,
+  };
+
+  let place = |r#type: Tetromino, hold: Option<Tetromino>| {
+    remaining
+      .iter()
+      .enumerate()
+      .filter(|(_, piece)| piece.r#type == r#type && can_drop(field, piece))
+      .any(|(i, piece)| {
+        let mut field = field.clone();
+        field.settle_tetromino(piece);
+        let mut remaining = remaining.to_vec();
+        remaining.remove(i);
+        is_possible(&field, &remaining, rest, hold, true)
+      })
+  };
+
+  if place(current, hold) {
+    return true;
+  }
+  if !can_hold {
+    return false;
+  }
+  match hold {
+    Some(held) => place(held, Some(current)),
+    None => is_possible(field, remaining, rest, Some(current), false),
+  }
+}
+
+// Resting on the stack with nothing above it, so a hard drop reaches it.
+pub fn can_drop(field: &Field, piece: &CurrentTetromino) -> bool {
+  let blocks = piece.blocks();
+  let is_grounded = blocks
+    .iter()
+    .any(|&(x, y)| y == 0 || field.get_cell(x, y - 1) != FieldCellValue::None);
+  let is_open = blocks
+    .iter()
+    .all(|&(x, y)| (y..MAX_FIELD_HEIGHT).all(|y| field.get_cell(x, y) == FieldCellValue::None));
+
+  is_grounded && is_open
+}
+
+// Each piece of a line is a different type, so the cells of a colour make up one piece.
+fn pieces(field: &Field) -> Vec<CurrentTetromino> {
+  let directions = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+  ];
+  let types = [
+    Tetromino::I,
+    Tetromino::J,
+    Tetromino::L,
+    Tetromino::O,
+    Tetromino::S,
+    Tetromino::T,
+    Tetromino::Z,
+  ];
+
+  types
+    .iter()
+    .filter_map(|&r#type| {
+      let cells: Vec<XY> = (0..MAX_FIELD_HEIGHT)
+        .flat_map(|y| (0..MAX_FIELD_WIDTH).map(move |x| (x, y)))
+        .filter(|&(x, y)| field.get_cell(x, y) == FieldCellValue::from(r#type))
+        .collect();
+      directions.iter().find_map(|&direction| {
+        Placement::from_blocks(CoordinateSystem::Tetsimu2, r#type, direction, &cells)
+      })
+    })
+    .map(|x| x.to_current_tetromino())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use Tetromino::*;
+
+  fn state(field: Field, queue: &[Tetromino], hold: Option<Tetromino>) -> TutorState {
+    TutorState {
+      field,
+      queue: queue.to_vec(),
+      hold,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    }
+  }
+
+  fn built(variant: &str, types: &[Tetromino]) -> Field {
+    let line = lines().into_iter().find(|x| x.variant == variant).unwrap();
+    let mut field = Field::new();
+    for piece in line.pieces.iter().filter(|x| types.contains(&x.r#type)) {
+      field.settle_tetromino(piece);
+    }
+    field
+  }
+
+  #[test]
+  fn bundled_fumens() {
+    let decoder = TetfuDecoder::new();
+    for (line, &(_, fumen)) in lines().iter().zip(CATALOG.iter()) {
+      let field = decoder.decode(String::from(fumen)).unwrap().field;
+      let occupied = field
+        .data
+        .iter()
+        .filter(|&&cell| cell != FieldCellValue::None)
+        .count();
+      assert_eq!(line.pieces.len(), 6, "{}", line.variant);
+      assert_eq!(line.pieces.len() * 4, occupied, "{}", line.variant);
+    }
+  }
+
+  #[test]
+  fn recognize_openers() {
+    let actual = recognize(&state(built("TKI", &[I, L]), &[O, J, Z, S, T], None));
+    let variants: Vec<_> = actual.iter().map(|x| x.variant.as_str()).collect();
+    assert_eq!(variants, vec!["TKI", "Albatross"]);
+    assert_eq!(actual[0].placed, 2);
+    assert_eq!(actual[0].total, 6);
+    assert_eq!(actual[0].is_possible, true);
+
+    let actual = recognize(&state(built("DT Cannon", &[J, L]), &[T, S], None));
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].opener, OpenerOption::DtCannon);
+  }
+
+  #[test]
+  fn recognize_impossible_queue() {
+    // The S comes first and would cover the Z, while the hold is taken by the T.
+    let actual = recognize(&state(built("TKI", &[I, L, O, J]), &[S, Z, I], Some(T)));
+    assert_eq!(actual[0].variant, "TKI");
+    assert_eq!(actual[0].is_possible, false);
+
+    let actual = recognize(&state(built("TKI", &[I, L, O, J]), &[Z, S, I], Some(T)));
+    assert_eq!(actual[0].is_possible, true);
+  }
+
+  #[test]
+  fn deviated() {
+    let prev = recognize(&state(built("PCO", &[J, L]), &[O], None));
+    assert_eq!(prev.len(), 1);
+
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "JNNNNLNNOO",
+      "JJJLLLNNOO",
+    ].concat());
+    let next = state(field, &[S], None);
+    assert_eq!(deviated_from(&prev, &next), vec![OpenerOption::Pco]);
+
+    let next = state(built("PCO", &[J, L, O]), &[S], None);
+    assert_eq!(deviated_from(&prev, &next), vec![]);
+    let next = state(Field::new(), &[S], None);
+    assert_eq!(deviated_from(&prev, &next), vec![]);
+  }
+}
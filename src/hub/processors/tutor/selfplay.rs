@@ -0,0 +1,516 @@
+use crate::hub::processors::tutor::bot_options::ThinkBudget;
+use crate::hub::processors::tutor::bot_session::BotSession;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::tutor_backend::Suggestion;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::tetfu::core::Tetsimu2Content;
+use crate::tetfu::tetfu_encoder::TetfuEncoder;
+use crate::tetsimu2::attack::AttackState;
+use crate::tetsimu2::attack::LineClear;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::field_conductor::FieldConductor;
+use crate::tetsimu2::next_generator::RandomNextGenerator;
+use crate::tetsimu2::router::RouteSearcher;
+use crate::tetsimu2::router::SearchRouteAction;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+const PREVIEW: usize = 5;
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+// A bot that has not suggested anything for this long is given up on.
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Bot {
+  pub backend: Box<dyn TutorBackend>,
+  pub budget: ThinkBudget,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEnd {
+  // Still playing when the game ended, by the piece limit or the opponent's top out.
+  Alive,
+  TopOut,
+  // The bot did not suggest a move in time.
+  Stalled,
+  // The suggested move could not be played from the spawn position.
+  Unreachable,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMove {
+  pub hold: bool,
+  pub r#type: u8,
+  pub dir: u8,
+  pub x: i8,
+  pub y: i8,
+  pub lines: u32,
+  pub attack: u32,
+  pub garbage: u32,
+  // The field after the move and the garbage it let in, so the moves replay page by page.
+  pub fumen: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerRecord {
+  pub end: GameEnd,
+  pub attack: u32,
+  pub pcs: u32,
+  pub think_ms: u64,
+  pub moves: Vec<ReplayMove>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+  pub seed: u64,
+  pub winner: Option<usize>,
+  pub players: Vec<PlayerRecord>,
+}
+
+struct Player {
+  conductor: FieldConductor,
+  session: BotSession,
+  budget: ThinkBudget,
+  attack_state: AttackState,
+  pending_garbage: u32,
+  hole_rng: StdRng,
+  attack: u32,
+  pcs: u32,
+  think_time: Duration,
+  moves: Vec<ReplayMove>,
+}
+
+impl Player {
+  fn new(bot: Bot, seed: u64) -> Self {
+    let gen = RandomNextGenerator::with_seed(vec![], seed);
+    let mut conductor = FieldConductor::new(Box::new(gen));
+    let state = tutor_state(&mut conductor, &AttackState::default());
+
+    Player {
+      conductor,
+      session: BotSession::start(bot.backend, state),
+      budget: bot.budget,
+      attack_state: AttackState::default(),
+      pending_garbage: 0,
+      hole_rng: StdRng::seed_from_u64(seed),
+      attack: 0,
+      pcs: 0,
+      think_time: Duration::ZERO,
+      moves: vec![],
+    }
+  }
+
+  // Plays one piece and returns the garbage lines it sends after cancelling incoming ones.
+  fn play(&mut self) -> Result<u32, GameEnd> {
+    let mv = self.think()?.mv;
+    if mv.hold && !self.conductor.hold() {
+      return Err(GameEnd::Unreachable);
+    }
+
+    let start = self.conductor.current().clone();
+    if start.r#type != mv.piece.r#type {
+      return Err(GameEnd::Unreachable);
+    }
+    let route_searcher = RouteSearcher {
+      field: self.conductor.field(),
+    };
+    let actions = route_searcher
+      .search_route(start, &mv.piece)
+      .ok_or(GameEnd::Unreachable)?;
+    for action in actions {
+      match action {
+        SearchRouteAction::MoveLeft => self.conductor.move_left(),
+        SearchRouteAction::MoveRight => self.conductor.move_right(),
+        SearchRouteAction::TurnLeft => self.conductor.turn_left(),
+        SearchRouteAction::TurnRight => self.conductor.turn_right(),
+        SearchRouteAction::SoftDrop => self.conductor.soft_drop(),
+        SearchRouteAction::Turn180 => return Err(GameEnd::Unreachable),
+      };
+    }
+
+    let lines = self.conductor.hard_drop();
+    let is_pc = lines > 0
+      && self
+        .conductor
+        .field()
+        .data
+        .iter()
+        .all(|&cell| cell == FieldCellValue::None);
+    let attack = self.attack_state.attack(&LineClear {
+      lines,
+      is_tspin: mv.is_spin && mv.piece.r#type == Tetromino::T,
+      is_pc,
+    });
+    let cancelled = attack.min(self.pending_garbage);
+    self.pending_garbage -= cancelled;
+
+    let mut garbage = 0;
+    if lines == 0 && self.pending_garbage > 0 && !self.conductor.is_dead() {
+      garbage = std::mem::take(&mut self.pending_garbage);
+      let hole_x = self.hole_rng.gen_range(0..MAX_FIELD_WIDTH);
+      self.conductor.receive_garbage(garbage, hole_x);
+    }
+
+    self.attack += attack;
+    if is_pc {
+      self.pcs += 1;
+    }
+    self.moves.push(ReplayMove {
+      hold: mv.hold,
+      r#type: mv.piece.r#type as u8,
+      dir: mv.piece.direction as u8,
+      x: mv.piece.x as i8,
+      y: mv.piece.y as i8,
+      lines,
+      attack,
+      garbage,
+      fumen: TetfuEncoder::new().encode(&Tetsimu2Content {
+        field: self.conductor.field().clone(),
+        comment: String::new(),
+      }),
+    });
+
+    if self.conductor.is_dead() {
+      return Err(GameEnd::TopOut);
+    }
+
+    let state = tutor_state(&mut self.conductor, &self.attack_state);
+    self.session.update(state);
+    Ok(attack - cancelled)
+  }
+
+  // Polls the bot until its suggestion is final under the budget, or takes the first one without
+  // a budget. A budget the bot never reports reaching, such as nodes above its own limit or a bot
+  // that sends no node counts, ends at the stall timeout.
+  fn think(&mut self) -> Result<Suggestion, GameEnd> {
+    let has_budget = self.budget.time.is_some() || self.budget.nodes.is_some();
+    let limit = self
+      .budget
+      .time
+      .map_or(STALL_TIMEOUT, |x| x.max(STALL_TIMEOUT));
+    let started = Instant::now();
+    let mut latest = None;
+
+    loop {
+      let elapsed = started.elapsed();
+      if let Some(x) = self.session.suggest() {
+        latest = Some(x);
+      }
+
+      match &latest {
+        Some(x) if !has_budget || self.budget.is_reached(elapsed, x.nodes) => break,
+        Some(_) if elapsed >= limit => break,
+        None if elapsed >= STALL_TIMEOUT => return Err(GameEnd::Stalled),
+        _ => thread::sleep(POLL_INTERVAL),
+      }
+    }
+
+    self.think_time += started.elapsed();
+    latest.ok_or(GameEnd::Stalled)
+  }
+
+  fn record(self, end: GameEnd) -> PlayerRecord {
+    PlayerRecord {
+      end,
+      attack: self.attack,
+      pcs: self.pcs,
+      think_ms: self.think_time.as_millis() as u64,
+      moves: self.moves,
+    }
+  }
+}
+
+fn tutor_state(conductor: &mut FieldConductor, attack_state: &AttackState) -> TutorState {
+  let mut queue = vec![conductor.current().r#type];
+  queue.extend(conductor.nexts(PREVIEW));
+
+  TutorState {
+    field: conductor.field().clone(),
+    queue,
+    hold: conductor.holded(),
+    combo: attack_state.combo,
+    is_btb: attack_state.is_btb,
+    can_hold: conductor.can_hold(),
+  }
+}
+
+// Every bot plays the same piece sequence, taking turns one piece at a time. With two bots each
+// sends its attack to the other, and the game ends when either tops out.
+pub fn play_game(bots: Vec<Bot>, seed: u64, max_pieces: usize) -> GameRecord {
+  let mut players: Vec<_> = bots.into_iter().map(|bot| Player::new(bot, seed)).collect();
+  let mut ends = vec![GameEnd::Alive; players.len()];
+  let count = players.len();
+
+  'game: for _ in 0..max_pieces {
+    for i in 0..count {
+      match players[i].play() {
+        Ok(attack) => {
+          if count > 1 {
+            players[(i + 1) % count].pending_garbage += attack;
+          }
+        }
+        Err(end) => {
+          ends[i] = end;
+          break 'game;
+        }
+      }
+    }
+  }
+
+  let winner = match ends.iter().position(|&end| end != GameEnd::Alive) {
+    Some(loser) if count == 2 => Some(1 - loser),
+    _ => None,
+  };
+
+  GameRecord {
+    seed,
+    winner,
+    players: players
+      .into_iter()
+      .zip(ends)
+      .map(|(player, end)| player.record(end))
+      .collect(),
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+  pub games: u32,
+  pub pieces: u32,
+  pub attack: u32,
+  pub pcs: u32,
+  pub deaths: u32,
+  pub failures: u32,
+  pub wins: u32,
+  pub think_ms: u64,
+}
+
+impl Summary {
+  pub fn add(&mut self, game: &GameRecord, player: usize) {
+    let record = &game.players[player];
+    self.games += 1;
+    self.pieces += record.moves.len() as u32;
+    self.attack += record.attack;
+    self.pcs += record.pcs;
+    self.think_ms += record.think_ms;
+    match record.end {
+      GameEnd::TopOut => self.deaths += 1,
+      GameEnd::Stalled | GameEnd::Unreachable => self.failures += 1,
+      GameEnd::Alive => {}
+    }
+    if game.winner == Some(player) {
+      self.wins += 1;
+    }
+  }
+
+  pub fn attack_per_piece(&self) -> f64 {
+    ratio(self.attack as f64, self.pieces as f64)
+  }
+
+  // Pieces per second of thinking, as the bots are not limited by a handling speed.
+  pub fn pieces_per_second(&self) -> f64 {
+    ratio(self.pieces as f64 * 1000.0, self.think_ms as f64)
+  }
+
+  pub fn death_rate(&self) -> f64 {
+    ratio(self.deaths as f64, self.games as f64)
+  }
+}
+
+fn ratio(a: f64, b: f64) -> f64 {
+  if b == 0.0 {
+    0.0
+  } else {
+    a / b
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::hub::processors::tutor::candidates::candidates;
+  use crate::hub::processors::tutor::tutor_backend::BotMove;
+  use crate::tetfu::tetfu_decoder::TetfuDecoder;
+  use crate::tetsimu2::core::DropType;
+  use cold_clear::evaluation::Standard;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  // Plays the best scored placement, so games are deterministic.
+  struct GreedyBackend {
+    state: Option<TutorState>,
+    starts: Arc<AtomicUsize>,
+  }
+
+  impl TutorBackend for GreedyBackend {
+    fn start(&mut self, state: &TutorState) -> Result<(), String> {
+      self.state = Some(state.clone());
+      self.starts.fetch_add(1, Ordering::Relaxed);
+      Ok(())
+    }
+
+    fn play(&mut self, mv: &BotMove) -> Result<(), String> {
+      self.state = self.state.as_ref().and_then(|x| x.after(mv));
+      Ok(())
+    }
+
+    fn add_pieces(&mut self, pieces: &[Tetromino]) -> Result<(), String> {
+      if let Some(state) = &mut self.state {
+        state.queue.extend(pieces);
+      }
+      Ok(())
+    }
+
+    fn suggest(&mut self) -> Option<Suggestion> {
+      let state = self.state.as_ref()?;
+      let best = candidates(state, DropType::SoftDrop, &Standard::default())
+        .into_iter()
+        .next()?;
+      Some(Suggestion {
+        mv: BotMove {
+          hold: best.hold,
+          piece: best.piece,
+          is_spin: false,
+        },
+        plan: vec![],
+        depth: 1,
+        nodes: 1,
+        is_pc_loop: false,
+      })
+    }
+  }
+
+  fn greedy() -> Bot {
+    Bot {
+      backend: Box::new(GreedyBackend {
+        state: None,
+        starts: Arc::default(),
+      }),
+      budget: ThinkBudget::default(),
+    }
+  }
+
+  #[test]
+  fn play_game_with_same_seed() {
+    let first = play_game(vec![greedy()], 7, 30);
+    let second = play_game(vec![greedy()], 7, 30);
+
+    assert_eq!(first.winner, None);
+    assert_eq!(first.players[0].end, GameEnd::Alive);
+    assert_eq!(first.players[0].moves.len(), 30);
+    assert_eq!(first.players[0].moves, second.players[0].moves);
+  }
+
+  #[test]
+  fn play_versus_game() {
+    let record = play_game(vec![greedy(), greedy()], 3, 20);
+
+    assert_eq!(record.players.len(), 2);
+    assert_eq!(record.players[0].moves, record.players[1].moves);
+  }
+
+  #[test]
+  fn keep_bot_running_with_budget() {
+    let starts = Arc::new(AtomicUsize::new(0));
+    let bot = Bot {
+      backend: Box::new(GreedyBackend {
+        state: None,
+        starts: starts.clone(),
+      }),
+      budget: ThinkBudget {
+        time: None,
+        nodes: Some(1),
+      },
+    };
+
+    let record = play_game(vec![bot], 7, 10);
+    assert_eq!(record.players[0].moves.len(), 10);
+    assert_eq!(starts.load(Ordering::Relaxed), 1);
+  }
+
+  #[test]
+  fn receive_pending_garbage() {
+    let mut player = Player::new(greedy(), 1);
+    player.pending_garbage = 3;
+    assert_eq!(player.play(), Ok(0));
+
+    assert_eq!(player.pending_garbage, 0);
+    assert_eq!(player.moves[0].garbage, 3);
+    let garbage_cells = player
+      .conductor
+      .field()
+      .data
+      .iter()
+      .filter(|&&cell| cell == FieldCellValue::Garbage)
+      .count();
+    assert_eq!(garbage_cells, 3 * 9);
+
+    let page = TetfuDecoder::new().decode(player.moves[0].fumen.clone());
+    assert_eq!(&page.unwrap().field, player.conductor.field());
+  }
+
+  #[test]
+  fn summary() {
+    let player = |end, attack, pcs, pieces| PlayerRecord {
+      end,
+      attack,
+      pcs,
+      think_ms: 500,
+      moves: vec![
+        ReplayMove {
+          hold: false,
+          r#type: 1,
+          dir: 0,
+          x: 4,
+          y: 0,
+          lines: 0,
+          attack: 0,
+          garbage: 0,
+          fumen: String::new(),
+        };
+        pieces
+      ],
+    };
+    let games = [
+      GameRecord {
+        seed: 0,
+        winner: Some(0),
+        players: vec![
+          player(GameEnd::Alive, 6, 1, 10),
+          player(GameEnd::TopOut, 2, 0, 10),
+        ],
+      },
+      GameRecord {
+        seed: 1,
+        winner: None,
+        players: vec![
+          player(GameEnd::Alive, 4, 0, 10),
+          player(GameEnd::Alive, 4, 0, 10),
+        ],
+      },
+    ];
+
+    let mut summary = Summary::default();
+    for game in &games {
+      summary.add(game, 1);
+    }
+    assert_eq!(summary.games, 2);
+    assert_eq!(summary.pieces, 20);
+    assert_eq!(summary.attack_per_piece(), 0.3);
+    assert_eq!(summary.pieces_per_second(), 20.0);
+    assert_eq!(summary.death_rate(), 0.5);
+    assert_eq!(summary.wins, 0);
+
+    let mut summary = Summary::default();
+    summary.add(&games[0], 0);
+    assert_eq!(summary.pcs, 1);
+    assert_eq!(summary.wins, 1);
+  }
+}
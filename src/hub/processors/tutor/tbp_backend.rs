@@ -0,0 +1,485 @@
+use crate::coordinates::CoordinateSystem;
+use crate::coordinates::Placement;
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::tutor_backend::BotMove;
+use crate::hub::processors::tutor::tutor_backend::Suggestion;
+use crate::hub::processors::tutor::tutor_backend::TutorBackend;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use log::{debug, info, warn};
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+const TBP_BOARD_HEIGHT: usize = 40;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const SUGGESTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FrontendMessage {
+  Rules {
+    randomizer: String,
+  },
+  Start {
+    hold: Option<String>,
+    queue: Vec<String>,
+    combo: u32,
+    back_to_back: bool,
+    board: Vec<Vec<Option<String>>>,
+  },
+  Stop,
+  Suggest,
+  Play {
+    #[serde(rename = "move")]
+    mv: TbpMove,
+  },
+  NewPiece {
+    piece: String,
+  },
+  Quit,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BotMessage {
+  Info {
+    name: String,
+    version: String,
+  },
+  Ready,
+  Error {
+    reason: String,
+  },
+  Suggestion {
+    moves: Vec<TbpMove>,
+    #[serde(default)]
+    move_info: Option<TbpMoveInfo>,
+  },
+  #[serde(other)]
+  Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct TbpMove {
+  location: TbpLocation,
+  spin: TbpSpin,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct TbpLocation {
+  #[serde(rename = "type")]
+  r#type: String,
+  orientation: TbpOrientation,
+  x: i32,
+  y: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum TbpOrientation {
+  North,
+  East,
+  South,
+  West,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum TbpSpin {
+  None,
+  Mini,
+  Full,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+struct TbpMoveInfo {
+  #[serde(default)]
+  nodes: f64,
+  #[serde(default)]
+  depth: f64,
+}
+
+// Talks to an external bot speaking the Tetris Bot Protocol with one JSON message per line.
+pub struct TbpBackend {
+  child: Child,
+  stdin: ChildStdin,
+  messages: Receiver<BotMessage>,
+  queue: Vec<Tetromino>,
+  hold: Option<Tetromino>,
+  can_hold: bool,
+  last_move: Option<TbpMove>,
+  is_waiting: bool,
+  is_started: bool,
+}
+
+impl TbpBackend {
+  pub fn launch(command: &str, args: &[String]) -> Result<Self, String> {
+    info!("Launch TBP bot. {} {}", command, args.join(" "));
+
+    let mut child = Command::new(command)
+      .args(args)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .map_err(|e| format!("Failed to launch TBP bot. {}", e))?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+
+    let (message_s, message_r) = mpsc::channel();
+    thread::spawn(move || {
+      for line in BufReader::new(stdout).lines() {
+        let line = match line {
+          Ok(x) => x,
+          Err(_) => break,
+        };
+        debug!("tbp <- {}", line);
+        match serde_json::from_str::<BotMessage>(&line) {
+          Ok(message) => {
+            if message_s.send(message).is_err() {
+              break;
+            }
+          }
+          Err(e) => warn!("Unexpected message from TBP bot. {}", e),
+        }
+      }
+    });
+
+    let mut backend = TbpBackend {
+      child,
+      stdin,
+      messages: message_r,
+      queue: vec![],
+      hold: None,
+      can_hold: true,
+      last_move: None,
+      is_waiting: false,
+      is_started: false,
+    };
+    backend.handshake()?;
+    Ok(backend)
+  }
+
+  fn handshake(&mut self) -> Result<(), String> {
+    match self.messages.recv_timeout(HANDSHAKE_TIMEOUT) {
+      Ok(BotMessage::Info { name, version }) => info!("TBP bot: {} {}", name, version),
+      Ok(message) => return Err(format!("Unexpected message from TBP bot. {:?}", message)),
+      Err(_) => return Err(String::from("TBP bot did not send info.")),
+    }
+
+    self.send(&FrontendMessage::Rules {
+      randomizer: String::from("seven_bag"),
+    })?;
+    match self.messages.recv_timeout(HANDSHAKE_TIMEOUT) {
+      Ok(BotMessage::Ready) => Ok(()),
+      Ok(BotMessage::Error { reason }) => Err(format!("TBP bot rejected the rules. {}", reason)),
+      Ok(message) => Err(format!("Unexpected message from TBP bot. {:?}", message)),
+      Err(_) => Err(String::from("TBP bot did not get ready.")),
+    }
+  }
+
+  fn send(&mut self, message: &FrontendMessage) -> Result<(), String> {
+    let json = serde_json::to_string(message).unwrap();
+    debug!("tbp -> {}", json);
+    writeln!(self.stdin, "{}", json)
+      .and_then(|_| self.stdin.flush())
+      .map_err(|e| format!("Failed to send to TBP bot. {}", e))
+  }
+
+  fn wait_suggestion(&mut self) {
+    if !self.is_waiting {
+      return;
+    }
+
+    while let Ok(message) = self.messages.recv_timeout(SUGGESTION_TIMEOUT) {
+      if let BotMessage::Suggestion { .. } = message {
+        break;
+      }
+    }
+    self.is_waiting = false;
+  }
+
+  // TBP has no hold flag; a move of another piece than the current one implies hold.
+  fn to_bot_move(&self, mv: &TbpMove) -> Option<BotMove> {
+    let piece = current_tetromino(&mv.location)?;
+    let hold = self.queue.first() != Some(&piece.r#type);
+    if hold && !self.can_hold {
+      return None;
+    }
+
+    Some(BotMove {
+      hold,
+      piece,
+      is_spin: mv.spin != TbpSpin::None,
+    })
+  }
+}
+
+impl TutorBackend for TbpBackend {
+  fn start(&mut self, state: &TutorState) -> Result<(), String> {
+    self.wait_suggestion();
+    if self.is_started {
+      self.send(&FrontendMessage::Stop)?;
+    }
+
+    self.queue = state.queue.clone();
+    self.hold = state.hold;
+    self.can_hold = state.can_hold;
+    self.last_move = None;
+    self.send(&FrontendMessage::Start {
+      hold: state.hold.map(piece_name),
+      queue: state.queue.iter().map(|&t| piece_name(t)).collect(),
+      combo: state.combo,
+      back_to_back: state.is_btb,
+      board: tbp_board(state),
+    })?;
+    self.is_started = true;
+    Ok(())
+  }
+
+  fn play(&mut self, mv: &BotMove) -> Result<(), String> {
+    self.wait_suggestion();
+    let last_move = match self.last_move.take() {
+      Some(x) => x,
+      None => return Err(String::from("TBP bot has not suggested a move.")),
+    };
+
+    if mv.hold {
+      let current = self.queue.remove(0);
+      if self.hold.replace(current).is_none() {
+        self.queue.remove(0);
+      }
+    } else {
+      self.queue.remove(0);
+    }
+
+    self.send(&FrontendMessage::Play { mv: last_move })
+  }
+
+  fn add_pieces(&mut self, pieces: &[Tetromino]) -> Result<(), String> {
+    for &piece in pieces {
+      self.queue.push(piece);
+      self.send(&FrontendMessage::NewPiece {
+        piece: piece_name(piece),
+      })?;
+    }
+    Ok(())
+  }
+
+  fn suggest(&mut self) -> Option<Suggestion> {
+    if !self.is_started {
+      return None;
+    }
+
+    if !self.is_waiting {
+      if let Err(e) = self.send(&FrontendMessage::Suggest) {
+        warn!("{}", e);
+        return None;
+      }
+      self.is_waiting = true;
+    }
+
+    let messages: Vec<_> = self.messages.try_iter().collect();
+    for message in messages {
+      match message {
+        BotMessage::Suggestion { moves, move_info } => {
+          self.is_waiting = false;
+          let (mv, bot_move) = moves
+            .iter()
+            .find_map(|mv| self.to_bot_move(mv).map(|x| (mv.clone(), x)))?;
+          let info = move_info.unwrap_or_default();
+          self.last_move = Some(mv);
+          return Some(Suggestion {
+            plan: vec![bot_move.piece.clone()],
+            mv: bot_move,
+            depth: info.depth as u32,
+            nodes: info.nodes as u32,
+            is_pc_loop: false,
+          });
+        }
+        BotMessage::Error { reason } => warn!("TBP bot error. {}", reason),
+        _ => {}
+      }
+    }
+
+    None
+  }
+}
+
+impl Drop for TbpBackend {
+  fn drop(&mut self) {
+    self.send(&FrontendMessage::Quit).ok();
+    thread::sleep(Duration::from_millis(100));
+    if let Ok(None) = self.child.try_wait() {
+      info!("Kill TBP bot.");
+      self.child.kill().ok();
+    }
+    self.child.wait().ok();
+  }
+}
+
+fn piece_name(r#type: Tetromino) -> String {
+  format!("{:?}", r#type)
+}
+
+fn tbp_board(state: &TutorState) -> Vec<Vec<Option<String>>> {
+  (0..TBP_BOARD_HEIGHT as i32)
+    .map(|y| {
+      (0..MAX_FIELD_WIDTH)
+        .map(|x| {
+          if y >= MAX_FIELD_HEIGHT {
+            return None;
+          }
+          match state.field.get_cell(x, y) {
+            FieldCellValue::None => None,
+            cell => Some(
+              Tetromino::from_u8(cell as u8)
+                .map(piece_name)
+                .unwrap_or_else(|| String::from("G")),
+            ),
+          }
+        })
+        .collect()
+    })
+    .collect()
+}
+
+fn current_tetromino(location: &TbpLocation) -> Option<CurrentTetromino> {
+  let r#type = location
+    .r#type
+    .chars()
+    .next()
+    .and_then(|c| Tetromino::try_from(c).ok())?;
+  let direction = match location.orientation {
+    TbpOrientation::North => Direction::Up,
+    TbpOrientation::East => Direction::Right,
+    TbpOrientation::South => Direction::Down,
+    TbpOrientation::West => Direction::Left,
+  };
+
+  let placement = Placement {
+    r#type,
+    direction,
+    x: location.x,
+    y: location.y,
+  };
+  Some(
+    placement
+      .convert(CoordinateSystem::Tbp, CoordinateSystem::Tetsimu2)
+      .to_current_tetromino(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::tetsimu2::field::Field;
+  use std::time::Instant;
+
+  const SCRIPTED_BOT: &str = "tests/fixtures/tbp/scripted_bot.sh";
+
+  #[test]
+  fn serialize_frontend_message() {
+    let actual = serde_json::to_string(&FrontendMessage::Play {
+      mv: TbpMove {
+        location: TbpLocation {
+          r#type: String::from("T"),
+          orientation: TbpOrientation::South,
+          x: 4,
+          y: 1,
+        },
+        spin: TbpSpin::Full,
+      },
+    })
+    .unwrap();
+    assert_eq!(
+      actual,
+      r#"{"type":"play","move":{"location":{"type":"T","orientation":"south","x":4,"y":1},"spin":"full"}}"#
+    );
+  }
+
+  #[test]
+  fn deserialize_bot_message() {
+    let actual = serde_json::from_str::<BotMessage>(
+      r#"{"type":"suggestion","moves":[{"location":{"type":"O","orientation":"north","x":4,"y":0},"spin":"none"}],"move_info":{"nodes":12,"nps":1.5,"depth":3,"extra":""}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+      actual,
+      BotMessage::Suggestion {
+        moves: vec![TbpMove {
+          location: TbpLocation {
+            r#type: String::from("O"),
+            orientation: TbpOrientation::North,
+            x: 4,
+            y: 0,
+          },
+          spin: TbpSpin::None,
+        }],
+        move_info: Some(TbpMoveInfo {
+          nodes: 12.0,
+          depth: 3.0,
+        }),
+      }
+    );
+
+    let actual = serde_json::from_str::<BotMessage>(r#"{"type":"pong"}"#).unwrap();
+    assert_eq!(actual, BotMessage::Unknown);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn suggest_with_scripted_bot() {
+    let mut backend = TbpBackend::launch("sh", &[String::from(SCRIPTED_BOT)]).unwrap();
+    let state = TutorState {
+      field: Field::new(),
+      queue: vec![Tetromino::T, Tetromino::O],
+      hold: None,
+      combo: 0,
+      is_btb: false,
+      can_hold: true,
+    };
+    backend.start(&state).unwrap();
+
+    let started_at = Instant::now();
+    let suggestion = loop {
+      if let Some(x) = backend.suggest() {
+        break x;
+      }
+      assert!(started_at.elapsed() < SUGGESTION_TIMEOUT);
+      thread::sleep(Duration::from_millis(10));
+    };
+
+    assert_eq!(
+      suggestion.mv,
+      BotMove {
+        hold: true,
+        piece: CurrentTetromino {
+          r#type: Tetromino::O,
+          direction: Direction::Up,
+          x: 4,
+          y: 0,
+        },
+        is_spin: false,
+      }
+    );
+    assert_eq!(suggestion.nodes, 10);
+    assert_eq!(suggestion.depth, 1);
+
+    backend.play(&suggestion.mv).unwrap();
+    assert_eq!(backend.queue, vec![]);
+    assert_eq!(backend.hold, Some(Tetromino::T));
+  }
+}
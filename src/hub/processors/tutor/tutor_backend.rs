@@ -0,0 +1,53 @@
+use crate::hub::processors::tutor::bot_session::TutorState;
+use crate::hub::processors::tutor::cold_clear_backend::ColdClearBackend;
+use crate::hub::processors::tutor::tbp_backend::TbpBackend;
+use crate::settings::Settings;
+use crate::settings::TutorBackendKind;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use cold_clear::evaluation::Standard;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotMove {
+  pub hold: bool,
+  pub piece: CurrentTetromino,
+  pub is_spin: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+  pub mv: BotMove,
+  pub plan: Vec<CurrentTetromino>,
+  pub depth: u32,
+  pub nodes: u32,
+  pub is_pc_loop: bool,
+}
+
+pub trait TutorBackend: Send {
+  fn start(&mut self, state: &TutorState) -> Result<(), String>;
+
+  // Called only with the move of the last suggestion, once the player has played it.
+  fn play(&mut self, mv: &BotMove) -> Result<(), String>;
+
+  fn add_pieces(&mut self, pieces: &[Tetromino]) -> Result<(), String>;
+
+  fn suggest(&mut self) -> Option<Suggestion>;
+}
+
+pub fn create_backend(
+  settings: &Settings,
+  kind: TutorBackendKind,
+  options: &cold_clear::Options,
+  evaluator: &Standard,
+) -> Result<Box<dyn TutorBackend>, String> {
+  match kind {
+    TutorBackendKind::ColdClear => Ok(Box::new(ColdClearBackend::new(options, evaluator))),
+    TutorBackendKind::Tbp => {
+      let command = match &settings.tbp.command {
+        Some(x) => x,
+        None => return Err(String::from("TBP bot command is not set.")),
+      };
+      Ok(Box::new(TbpBackend::launch(command, &settings.tbp.args)?))
+    }
+  }
+}
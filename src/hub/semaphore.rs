@@ -0,0 +1,104 @@
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub struct Semaphore {
+  available: Mutex<usize>,
+  cond: Condvar,
+}
+
+pub struct SemaphoreGuard<'a> {
+  semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+  pub fn new(permits: usize) -> Self {
+    Semaphore {
+      available: Mutex::new(permits),
+      cond: Condvar::new(),
+    }
+  }
+
+  pub fn try_acquire(&self) -> Option<SemaphoreGuard<'_>> {
+    let mut available = self.available.lock().unwrap();
+    if *available == 0 {
+      return None;
+    }
+
+    *available -= 1;
+    Some(SemaphoreGuard { semaphore: self })
+  }
+
+  pub fn acquire_unless<F>(&self, is_canceled: F) -> Option<SemaphoreGuard<'_>>
+  where
+    F: Fn() -> bool,
+  {
+    let mut available = self.available.lock().unwrap();
+    while *available == 0 {
+      if is_canceled() {
+        return None;
+      }
+
+      available = self
+        .cond
+        .wait_timeout(available, Duration::from_millis(100))
+        .unwrap()
+        .0;
+    }
+
+    *available -= 1;
+    Some(SemaphoreGuard { semaphore: self })
+  }
+
+  fn release(&self) {
+    let mut available = self.available.lock().unwrap();
+    *available += 1;
+    self.cond.notify_one();
+  }
+}
+
+impl<'a> Drop for SemaphoreGuard<'a> {
+  fn drop(&mut self) {
+    self.semaphore.release();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::Arc;
+  use std::thread;
+
+  #[test]
+  fn try_acquire() {
+    let semaphore = Semaphore::new(2);
+    let guard1 = semaphore.try_acquire();
+    let guard2 = semaphore.try_acquire();
+    assert_eq!(guard1.is_some(), true);
+    assert_eq!(guard2.is_some(), true);
+    assert_eq!(semaphore.try_acquire().is_none(), true);
+
+    drop(guard1);
+    assert_eq!(semaphore.try_acquire().is_some(), true);
+  }
+
+  #[test]
+  fn acquire_unless_canceled() {
+    let semaphore = Semaphore::new(1);
+    let _guard = semaphore.try_acquire();
+    assert_eq!(semaphore.acquire_unless(|| true).is_none(), true);
+  }
+
+  #[test]
+  fn acquire_unless_waits_for_release() {
+    let semaphore = Arc::new(Semaphore::new(1));
+    let guard = semaphore.try_acquire();
+
+    let semaphore2 = Arc::clone(&semaphore);
+    let handle = thread::spawn(move || semaphore2.acquire_unless(|| false).is_some());
+
+    thread::sleep(Duration::from_millis(50));
+    drop(guard);
+    assert_eq!(handle.join().unwrap(), true);
+  }
+}
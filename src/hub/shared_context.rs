@@ -0,0 +1,30 @@
+use crate::hub::analysis_cache::AnalysisCache;
+use crate::hub::processors::analyze_pc::sfinder_features::SfinderFeatures;
+use crate::hub::processors::analyze_pc::sfinder_probe::SfinderProbe;
+use crate::hub::semaphore::Semaphore;
+use crate::settings::Settings;
+use std::sync::Mutex;
+
+pub const DEFAULT_MAX_CONCURRENT_ANALYSES: usize = 2;
+
+pub struct SharedContext {
+  pub analysis_slots: Semaphore,
+  pub analysis_cache: Option<AnalysisCache>,
+  pub sfinder_features: Mutex<Option<SfinderFeatures>>,
+}
+
+impl SharedContext {
+  pub fn new(settings: &Settings, probe: &SfinderProbe) -> Self {
+    let max_concurrency = settings
+      .solution_finder
+      .max_concurrency
+      .unwrap_or(DEFAULT_MAX_CONCURRENT_ANALYSES)
+      .max(1);
+
+    SharedContext {
+      analysis_slots: Semaphore::new(max_concurrency),
+      analysis_cache: AnalysisCache::from_settings(&settings.cache),
+      sfinder_features: Mutex::new(probe.features.clone()),
+    }
+  }
+}
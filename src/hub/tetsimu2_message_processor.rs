@@ -4,6 +4,7 @@ use crate::hub::messages::tetsimu2::Tetsimu2Message;
 use crate::hub::processors;
 use crate::hub::processors::tetsimu2_processor::BeforeExecuteResult;
 use crate::hub::processors::tetsimu2_processor::Tetsimu2Processor;
+use crate::hub::shared_context::SharedContext;
 use crate::settings::Settings;
 use log::{debug, error, info};
 use std::sync::mpsc::Receiver;
@@ -14,16 +15,23 @@ pub struct Tetsimu2MessageProcessor {
   pub t2_r: Receiver<Tetsimu2Message>,
   pub out: ws::Sender,
   pub settings: Arc<Settings>,
+  pub context: Arc<SharedContext>,
   processor: Option<Arc<dyn Tetsimu2Processor>>,
 }
 
 impl Tetsimu2MessageProcessor {
-  pub fn start(t2_r: Receiver<Tetsimu2Message>, out: ws::Sender, settings: Arc<Settings>) {
+  pub fn start(
+    t2_r: Receiver<Tetsimu2Message>,
+    out: ws::Sender,
+    settings: Arc<Settings>,
+    context: Arc<SharedContext>,
+  ) {
     thread::spawn(|| {
       let mut processor = Tetsimu2MessageProcessor {
         t2_r,
         out,
         settings: settings,
+        context,
         processor: None,
       };
 
@@ -37,6 +45,10 @@ impl Tetsimu2MessageProcessor {
     loop {
       let received_message = self.t2_r.try_recv();
       match received_message {
+        // Stateless, so it is answered while another process runs.
+        Ok(Tetsimu2Message::AnalyzeTspin(m)) => {
+          processors::analyze_tspin::execute(&self.out, &m);
+        }
         Ok(message) => {
           if let Some(processor) = &self.processor {
             match processor.before_execute(&message) {
@@ -64,6 +76,7 @@ impl Tetsimu2MessageProcessor {
                   &self.out,
                   m,
                   &self.settings,
+                  &self.context,
                 ));
               }
               Tetsimu2Message::InitTutor(m) => {
@@ -1,6 +1,9 @@
+pub mod commands;
 pub mod constants;
+pub mod coordinates;
 mod dev_utils;
 pub mod hub;
+pub mod renderer;
 pub mod settings;
 pub mod tetfu;
 pub mod tetsimu2;
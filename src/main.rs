@@ -5,20 +5,35 @@ use std::io::stdout;
 use std::io::Write;
 use std::path::Path;
 use std::sync::Arc;
+use tetsimu2_hub::commands;
 use tetsimu2_hub::constants::HUB_VERSION;
 use tetsimu2_hub::hub::hub_server::HubServer;
+use tetsimu2_hub::hub::processors::analyze_pc::sfinder_probe::SfinderProbe;
+use tetsimu2_hub::settings::CacheSettings;
 use tetsimu2_hub::settings::HubSettings;
 use tetsimu2_hub::settings::Settings;
 use tetsimu2_hub::settings::SolutionFinderSettings;
+use tetsimu2_hub::settings::TbpSettings;
+use tetsimu2_hub::settings::TutorOptions;
 
-fn start_server(settings: Settings) -> Result<()> {
-    HubServer::listen(Arc::new(settings))
+fn start_server(settings: Settings, probe: SfinderProbe) -> Result<()> {
+    HubServer::listen(Arc::new(settings), probe)
 }
 
 const CONFIG_FILE: &str = "config.toml";
 
 fn main() {
     env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        if let Err(e) = commands::execute(command, &args[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("Tetsimu2 Hub {}", HUB_VERSION);
 
     println!("Load {}", CONFIG_FILE);
@@ -32,13 +47,16 @@ fn main() {
 
     println!("{}", config);
 
+    let probe = SfinderProbe::run(&config.solution_finder);
+    println!("{}", probe);
+
     println!(
         "Starting websocket server. Listening on {}:{}",
         config.hub.host, config.hub.port
     );
     println!("Press Ctrl + c to Exit");
 
-    if let Err(e) = start_server(config) {
+    if let Err(e) = start_server(config, probe) {
         eprintln!("{:?}", e);
     }
 }
@@ -58,11 +76,19 @@ fn init_config(path: &str) -> Result<Settings, String> {
     let host = input_host()?;
     let port = input_port()?;
     let sf_path = input_sf_path()?;
+    let java_path = input_java_path()?;
     println!("");
 
     let settings = Settings {
         hub: HubSettings { host, port },
-        solution_finder: SolutionFinderSettings { path: sf_path },
+        solution_finder: SolutionFinderSettings {
+            path: sf_path,
+            java_path,
+            ..Default::default()
+        },
+        cache: CacheSettings::default(),
+        tutor: TutorOptions::default(),
+        tbp: TbpSettings::default(),
     };
 
     if let Err(e) = settings.write_file(&format!("{}", path)) {
@@ -118,3 +144,17 @@ fn input_sf_path() -> Result<Option<String>, String> {
         return Ok(Some(sf_path));
     }
 }
+
+fn input_java_path() -> Result<Option<String>, String> {
+    print!("Java executable path(optional): ");
+    stdout().flush().ok();
+
+    let mut input = String::new();
+    stdin().read_line(&mut input).map_err(|e| e.to_string())?;
+    let java_path = String::from(input.trim());
+    if java_path.is_empty() {
+        return Ok(None);
+    } else {
+        return Ok(Some(java_path));
+    }
+}
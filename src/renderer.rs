@@ -0,0 +1,5 @@
+pub mod core;
+pub mod gif_renderer;
+pub mod scene;
+pub mod skin;
+pub mod svg_renderer;
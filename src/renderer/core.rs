@@ -0,0 +1,111 @@
+use crate::renderer::skin::Skin;
+use crate::tetsimu2::core::Step;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_INNER_FIELD_HEIGHT;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RenderContent {
+  pub field: Field,
+  pub current: Option<CurrentTetromino>,
+  pub hold: Option<Tetromino>,
+  pub nexts: Vec<Tetromino>,
+}
+
+impl RenderContent {
+  pub fn new(field: Field) -> RenderContent {
+    RenderContent {
+      field,
+      current: None,
+      hold: None,
+      nexts: vec![],
+    }
+  }
+
+  pub fn sequence_from_steps(field: &Field, steps: &[Step]) -> Vec<RenderContent> {
+    let mut contents = vec![];
+    let mut field = field.clone();
+
+    for (i, step) in steps.iter().enumerate() {
+      let current = CurrentTetromino {
+        r#type: step.r#type,
+        direction: step.dir,
+        x: step.x as i32,
+        y: step.y as i32,
+      };
+
+      contents.push(RenderContent {
+        field: field.clone(),
+        current: Some(current.clone()),
+        hold: None,
+        nexts: steps[i + 1..].iter().map(|step| step.r#type).collect(),
+      });
+
+      field.settle_tetromino(&current);
+      field.clear_lines();
+    }
+
+    contents.push(RenderContent::new(field));
+    contents
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RenderOptions {
+  pub cell_size: u32,
+  pub field_height: i32,
+  pub max_nexts: usize,
+  pub skin: Skin,
+}
+
+impl Default for RenderOptions {
+  fn default() -> Self {
+    RenderOptions {
+      cell_size: 16,
+      field_height: MAX_INNER_FIELD_HEIGHT,
+      max_nexts: 5,
+      skin: Skin::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+  use crate::tetsimu2::core::Direction;
+
+  #[test]
+  fn sequence_from_steps() {
+    #[rustfmt::skip]
+    let field = make_field("GGGGGGNNNN");
+    let steps = vec![
+      Step {
+        r#type: Tetromino::I,
+        dir: Direction::Up,
+        x: 7,
+        y: 0,
+      },
+      Step {
+        r#type: Tetromino::O,
+        dir: Direction::Up,
+        x: 0,
+        y: 0,
+      },
+    ];
+
+    let contents = RenderContent::sequence_from_steps(&field, &steps);
+
+    assert_eq!(contents.len(), 3);
+    assert_eq!(contents[0].field, field);
+    assert_eq!(contents[0].nexts, vec![Tetromino::O]);
+    assert_eq!(contents[1].field, Field::new());
+    assert_eq!(contents[1].nexts, vec![]);
+    #[rustfmt::skip]
+    assert_eq!(contents[2].field, make_field(&format!("{}{}",
+      "OONNNNNNNN",
+      "OONNNNNNNN")));
+    assert_eq!(contents[2].current, None);
+  }
+}
@@ -0,0 +1,182 @@
+use crate::renderer::core::RenderContent;
+use crate::renderer::core::RenderOptions;
+use crate::renderer::scene::Scene;
+use crate::renderer::skin::Color;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+const MAX_PALETTE_SIZE: usize = 256;
+
+pub struct GifRenderer {
+  options: RenderOptions,
+  frame_delay: u16,
+}
+
+struct Canvas {
+  width: u32,
+  height: u32,
+  pixels: Vec<Color>,
+}
+
+impl Canvas {
+  fn from_scene(scene: &Scene) -> Canvas {
+    let mut canvas = Canvas {
+      width: scene.width,
+      height: scene.height,
+      pixels: vec![scene.background; (scene.width * scene.height) as usize],
+    };
+
+    for rect in scene.rects.iter() {
+      let right = (rect.x + rect.width).min(canvas.width);
+      let bottom = (rect.y + rect.height).min(canvas.height);
+      for y in rect.y..bottom {
+        for x in rect.x..right {
+          let p = (y * canvas.width + x) as usize;
+          canvas.pixels[p] = if rect.opacity < 1.0 {
+            rect.color.blend(&canvas.pixels[p], rect.opacity)
+          } else {
+            rect.color
+          };
+        }
+      }
+    }
+
+    canvas
+  }
+}
+
+impl GifRenderer {
+  pub fn new(options: RenderOptions, frame_delay: u16) -> GifRenderer {
+    GifRenderer {
+      options,
+      frame_delay,
+    }
+  }
+
+  pub fn render(&self, contents: &[RenderContent]) -> Result<Vec<u8>, String> {
+    if contents.is_empty() {
+      return Err(String::from("No frames to render."));
+    }
+
+    let scenes: Vec<Scene> = contents
+      .iter()
+      .map(|content| Scene::build(content, &self.options))
+      .collect();
+    let (width, height) = gif_size(&scenes[0])?;
+    let canvases: Vec<Canvas> = scenes.iter().map(Canvas::from_scene).collect();
+
+    let mut palette: Vec<Color> = vec![];
+    let mut indexes: HashMap<Color, u8> = HashMap::new();
+    let mut frames = vec![];
+    for canvas in canvases.iter() {
+      let mut buffer = Vec::with_capacity(canvas.pixels.len());
+      for color in canvas.pixels.iter() {
+        let index = match indexes.get(color) {
+          Some(index) => *index,
+          None => {
+            if palette.len() >= MAX_PALETTE_SIZE {
+              return Err(String::from("Too many colors to encode gif."));
+            }
+
+            let index = palette.len() as u8;
+            palette.push(*color);
+            indexes.insert(*color, index);
+            index
+          }
+        };
+        buffer.push(index);
+      }
+
+      frames.push(gif::Frame {
+        width,
+        height,
+        buffer: Cow::Owned(buffer),
+        delay: self.frame_delay,
+        ..gif::Frame::default()
+      });
+    }
+
+    let palette_bytes: Vec<u8> = palette.iter().flat_map(|c| vec![c.0, c.1, c.2]).collect();
+    let mut image = vec![];
+    {
+      let mut encoder = gif::Encoder::new(&mut image, width, height, &palette_bytes)
+        .map_err(|e| format!("{:#}", e))?;
+      encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| format!("{:#}", e))?;
+      for frame in frames.iter() {
+        encoder.write_frame(frame).map_err(|e| format!("{:#}", e))?;
+      }
+    }
+
+    Ok(image)
+  }
+}
+
+// Every page is rendered with the same options, so they all share the first one's size.
+fn gif_size(scene: &Scene) -> Result<(u16, u16), String> {
+  match (u16::try_from(scene.width), u16::try_from(scene.height)) {
+    (Ok(width), Ok(height)) => Ok((width, height)),
+    _ => Err(format!(
+      "Image size {}x{} is too large for gif.",
+      scene.width, scene.height
+    )),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::renderer::skin::Skin;
+  use crate::tetsimu2::core::FieldCellValue;
+  use crate::tetsimu2::field::Field;
+
+  #[test]
+  fn render() {
+    let mut field = Field::new();
+    let first = RenderContent::new(field.clone());
+    field.set_cell(0, 0, FieldCellValue::I);
+    let second = RenderContent::new(field);
+
+    let renderer = GifRenderer::new(
+      RenderOptions {
+        cell_size: 4,
+        field_height: 2,
+        skin: Skin::fumen(),
+        ..RenderOptions::default()
+      },
+      50,
+    );
+
+    let image = renderer.render(&[first, second]).unwrap();
+    assert_eq!(&image[0..6], b"GIF89a");
+    assert_eq!(&image[6..10], &[80, 0, 8, 0]);
+    assert_eq!(image.last(), Some(&0x3b));
+  }
+
+  #[test]
+  fn render_should_fail_for_too_large_image() {
+    let renderer = GifRenderer::new(
+      RenderOptions {
+        cell_size: 4000,
+        field_height: 1,
+        ..RenderOptions::default()
+      },
+      50,
+    );
+    assert_eq!(
+      renderer.render(&[RenderContent::new(Field::new())]),
+      Err(String::from("Image size 80000x4000 is too large for gif."))
+    );
+  }
+
+  #[test]
+  fn render_should_fail_without_frames() {
+    let renderer = GifRenderer::new(RenderOptions::default(), 50);
+    assert_eq!(
+      renderer.render(&[]),
+      Err(String::from("No frames to render."))
+    );
+  }
+}
@@ -0,0 +1,244 @@
+use crate::renderer::core::RenderContent;
+use crate::renderer::core::RenderOptions;
+use crate::renderer::skin::Color;
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+
+const SIDE_PANEL_CELLS: u32 = 5;
+const NEXT_INTERVAL_CELLS: u32 = 3;
+const GHOST_OPACITY: f32 = 0.4;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rect {
+  pub x: u32,
+  pub y: u32,
+  pub width: u32,
+  pub height: u32,
+  pub color: Color,
+  pub opacity: f32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scene {
+  pub width: u32,
+  pub height: u32,
+  pub background: Color,
+  pub rects: Vec<Rect>,
+}
+
+fn visible_height(options: &RenderOptions) -> u32 {
+  options.field_height.max(1) as u32
+}
+
+impl Scene {
+  pub fn build(content: &RenderContent, options: &RenderOptions) -> Scene {
+    let cs = options.cell_size;
+    let field_x = SIDE_PANEL_CELLS * cs;
+    let field_height = visible_height(options);
+
+    let mut scene = Scene {
+      width: (SIDE_PANEL_CELLS * 2 + MAX_FIELD_WIDTH as u32) * cs,
+      height: field_height * cs,
+      background: options.skin.background,
+      rects: vec![],
+    };
+
+    scene.fill(0, 0, field_x, scene.height, options.skin.panel);
+    scene.fill(
+      field_x + MAX_FIELD_WIDTH as u32 * cs,
+      0,
+      SIDE_PANEL_CELLS * cs,
+      scene.height,
+      options.skin.panel,
+    );
+
+    if let Some(grid) = options.skin.grid {
+      scene.fill(field_x, 0, MAX_FIELD_WIDTH as u32 * cs, scene.height, grid);
+    }
+
+    for y in 0..field_height as i32 {
+      for x in 0..MAX_FIELD_WIDTH {
+        let cell = content.field.get_cell(x, y);
+        if cell == FieldCellValue::None && options.skin.grid.is_none() {
+          continue;
+        }
+
+        let (px, py) = scene.field_cell_position(x, y, options);
+        scene.fill_cell(px, py, cs, options.skin.cell_color(cell), 1.0);
+      }
+    }
+
+    if let Some(current) = &content.current {
+      let mut ghost = current.clone();
+      ghost.drop_to_bottom(&content.field);
+      let color = options.skin.cell_color(FieldCellValue::from(ghost.r#type));
+      for (x, y) in ghost.blocks() {
+        if y < 0 || y >= field_height as i32 {
+          continue;
+        }
+
+        let (px, py) = scene.field_cell_position(x, y, options);
+        scene.fill_cell(px, py, cs, color, GHOST_OPACITY);
+      }
+    }
+
+    if let Some(hold) = content.hold {
+      scene.draw_mini_tetromino(hold, cs / 2, cs, options);
+    }
+
+    let max_nexts = options
+      .max_nexts
+      .min((field_height.saturating_sub(1) / NEXT_INTERVAL_CELLS) as usize);
+    for (i, next) in content.nexts.iter().take(max_nexts).enumerate() {
+      scene.draw_mini_tetromino(
+        *next,
+        field_x + MAX_FIELD_WIDTH as u32 * cs + cs / 2,
+        cs + i as u32 * NEXT_INTERVAL_CELLS * cs,
+        options,
+      );
+    }
+
+    scene
+  }
+
+  fn field_cell_position(&self, x: i32, y: i32, options: &RenderOptions) -> (u32, u32) {
+    let cs = options.cell_size;
+    let px = (SIDE_PANEL_CELLS + x as u32) * cs;
+    let py = (visible_height(options) as i32 - 1 - y) as u32 * cs;
+    (px, py)
+  }
+
+  fn draw_mini_tetromino(
+    &mut self,
+    r#type: Tetromino,
+    left: u32,
+    top: u32,
+    options: &RenderOptions,
+  ) {
+    let cs = options.cell_size;
+    let tetromino = CurrentTetromino {
+      r#type,
+      direction: Direction::Up,
+      x: 0,
+      y: 0,
+    };
+    let blocks = tetromino.blocks();
+    let min_x = blocks.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let max_y = blocks.iter().map(|&(_, y)| y).max().unwrap_or(0);
+    let color = options.skin.cell_color(FieldCellValue::from(r#type));
+
+    for (x, y) in blocks {
+      let px = left + (x - min_x) as u32 * cs;
+      let py = top + (max_y - y) as u32 * cs;
+      self.fill_cell(px, py, cs, color, 1.0);
+    }
+  }
+
+  fn fill_cell(&mut self, x: u32, y: u32, cell_size: u32, color: Color, opacity: f32) {
+    let inset = if cell_size > 4 { 1 } else { 0 };
+    self.rects.push(Rect {
+      x,
+      y,
+      width: cell_size - inset,
+      height: cell_size - inset,
+      color,
+      opacity,
+    });
+  }
+
+  fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+    self.rects.push(Rect {
+      x,
+      y,
+      width,
+      height,
+      color,
+      opacity: 1.0,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::renderer::skin::Skin;
+  use crate::tetsimu2::field::Field;
+
+  #[test]
+  fn build_should_size_scene_by_cell_size() {
+    let options = RenderOptions {
+      cell_size: 10,
+      field_height: 4,
+      ..RenderOptions::default()
+    };
+    let scene = Scene::build(&RenderContent::new(Field::new()), &options);
+
+    assert_eq!(scene.width, 200);
+    assert_eq!(scene.height, 40);
+  }
+
+  #[test]
+  fn build_should_draw_field_cells_from_bottom() {
+    let mut field = Field::new();
+    field.set_cell(0, 0, FieldCellValue::T);
+    let options = RenderOptions {
+      cell_size: 10,
+      field_height: 4,
+      skin: Skin::fumen(),
+      ..RenderOptions::default()
+    };
+    let scene = Scene::build(&RenderContent::new(field), &options);
+
+    let t_color = options.skin.cell_color(FieldCellValue::T);
+    let cells: Vec<_> = scene.rects.iter().filter(|r| r.color == t_color).collect();
+    assert_eq!(cells.len(), 1);
+    assert_eq!((cells[0].x, cells[0].y), (50, 30));
+  }
+
+  #[test]
+  fn build_should_clamp_field_height() {
+    let mut field = Field::new();
+    field.set_cell(0, 0, FieldCellValue::T);
+    let options = RenderOptions {
+      cell_size: 10,
+      field_height: 0,
+      skin: Skin::fumen(),
+      ..RenderOptions::default()
+    };
+    let scene = Scene::build(&RenderContent::new(field), &options);
+
+    let t_color = options.skin.cell_color(FieldCellValue::T);
+    let cells: Vec<_> = scene.rects.iter().filter(|r| r.color == t_color).collect();
+    assert_eq!(scene.height, 10);
+    assert_eq!((cells[0].x, cells[0].y), (50, 0));
+  }
+
+  #[test]
+  fn build_should_draw_ghost_at_bottom() {
+    let options = RenderOptions {
+      cell_size: 10,
+      field_height: 4,
+      skin: Skin::fumen(),
+      ..RenderOptions::default()
+    };
+    let mut content = RenderContent::new(Field::new());
+    content.current = Some(CurrentTetromino {
+      r#type: Tetromino::O,
+      direction: Direction::Up,
+      x: 4,
+      y: 3,
+    });
+    let scene = Scene::build(&content, &options);
+
+    let ghosts: Vec<_> = scene
+      .rects
+      .iter()
+      .filter(|r| r.opacity < 1.0)
+      .map(|r| (r.x, r.y))
+      .collect();
+    assert_eq!(ghosts, vec![(90, 30), (90, 20), (100, 20), (100, 30)]);
+  }
+}
@@ -0,0 +1,132 @@
+use crate::tetsimu2::core::FieldCellValue;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+  pub fn to_hex(&self) -> String {
+    format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+  }
+
+  pub fn blend(&self, background: &Color, opacity: f32) -> Color {
+    let mix =
+      |fg: u8, bg: u8| -> u8 { (fg as f32 * opacity + bg as f32 * (1.0 - opacity)).round() as u8 };
+
+    Color(
+      mix(self.0, background.0),
+      mix(self.1, background.1),
+      mix(self.2, background.2),
+    )
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Skin {
+  pub name: String,
+  pub background: Color,
+  pub panel: Color,
+  pub grid: Option<Color>,
+  pub cells: [Color; 9],
+}
+
+impl Skin {
+  pub fn from_name(name: &str) -> Option<Skin> {
+    match name {
+      "default" => Some(Skin::default()),
+      "fumen" => Some(Skin::fumen()),
+      "monochrome" => Some(Skin::monochrome()),
+      _ => None,
+    }
+  }
+
+  pub fn fumen() -> Skin {
+    Skin {
+      name: String::from("fumen"),
+      background: Color(0x00, 0x00, 0x00),
+      panel: Color(0x20, 0x20, 0x20),
+      grid: None,
+      cells: [
+        Color(0x00, 0x00, 0x00),
+        Color(0x00, 0x99, 0x99),
+        Color(0x00, 0x00, 0xbb),
+        Color(0x99, 0x66, 0x00),
+        Color(0x99, 0x99, 0x00),
+        Color(0x00, 0x99, 0x00),
+        Color(0x99, 0x00, 0x99),
+        Color(0x99, 0x00, 0x00),
+        Color(0x99, 0x99, 0x99),
+      ],
+    }
+  }
+
+  pub fn monochrome() -> Skin {
+    let block = Color(0xcc, 0xcc, 0xcc);
+    Skin {
+      name: String::from("monochrome"),
+      background: Color(0xff, 0xff, 0xff),
+      panel: Color(0xf0, 0xf0, 0xf0),
+      grid: Some(Color(0xe0, 0xe0, 0xe0)),
+      cells: [
+        Color(0xff, 0xff, 0xff),
+        block,
+        block,
+        block,
+        block,
+        block,
+        block,
+        block,
+        Color(0x80, 0x80, 0x80),
+      ],
+    }
+  }
+
+  pub fn cell_color(&self, cell: FieldCellValue) -> Color {
+    self.cells[cell as usize]
+  }
+}
+
+impl Default for Skin {
+  fn default() -> Self {
+    Skin {
+      name: String::from("default"),
+      background: Color(0x10, 0x10, 0x18),
+      panel: Color(0x28, 0x28, 0x30),
+      grid: Some(Color(0x20, 0x20, 0x28)),
+      cells: [
+        Color(0x10, 0x10, 0x18),
+        Color(0x00, 0xc8, 0xe8),
+        Color(0x20, 0x50, 0xe0),
+        Color(0xf0, 0x90, 0x20),
+        Color(0xf0, 0xd0, 0x20),
+        Color(0x40, 0xc8, 0x40),
+        Color(0xb0, 0x40, 0xd0),
+        Color(0xe0, 0x30, 0x40),
+        Color(0x80, 0x80, 0x88),
+      ],
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn to_hex() {
+    assert_eq!(Color(0x00, 0x99, 0xff).to_hex(), "#0099ff");
+  }
+
+  #[test]
+  fn blend() {
+    let fg = Color(200, 100, 0);
+    let bg = Color(0, 0, 100);
+    assert_eq!(fg.blend(&bg, 0.5), Color(100, 50, 50));
+    assert_eq!(fg.blend(&bg, 1.0), fg);
+  }
+
+  #[test]
+  fn from_name() {
+    assert_eq!(Skin::from_name("fumen"), Some(Skin::fumen()));
+    assert_eq!(Skin::from_name("unknown"), None);
+  }
+}
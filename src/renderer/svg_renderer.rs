@@ -0,0 +1,82 @@
+use crate::renderer::core::RenderContent;
+use crate::renderer::core::RenderOptions;
+use crate::renderer::scene::Scene;
+
+pub struct SvgRenderer {
+  options: RenderOptions,
+}
+
+impl SvgRenderer {
+  pub fn new(options: RenderOptions) -> SvgRenderer {
+    SvgRenderer { options }
+  }
+
+  pub fn render(&self, content: &RenderContent) -> String {
+    let scene = Scene::build(content, &self.options);
+    let mut svg = vec![format!(
+      r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+      scene.width, scene.height, scene.width, scene.height
+    )];
+
+    svg.push(format!(
+      r#"<rect width="{}" height="{}" fill="{}"/>"#,
+      scene.width,
+      scene.height,
+      scene.background.to_hex()
+    ));
+
+    for rect in scene.rects.iter() {
+      if rect.opacity < 1.0 {
+        svg.push(format!(
+          r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+          rect.x,
+          rect.y,
+          rect.width,
+          rect.height,
+          rect.color.to_hex(),
+          rect.opacity
+        ));
+      } else {
+        svg.push(format!(
+          r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+          rect.x,
+          rect.y,
+          rect.width,
+          rect.height,
+          rect.color.to_hex()
+        ));
+      }
+    }
+
+    svg.push(String::from("</svg>"));
+    svg.join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::renderer::skin::Skin;
+  use crate::tetsimu2::core::FieldCellValue;
+  use crate::tetsimu2::field::Field;
+
+  #[test]
+  fn render() {
+    let mut field = Field::new();
+    field.set_cell(0, 0, FieldCellValue::I);
+    let renderer = SvgRenderer::new(RenderOptions {
+      cell_size: 10,
+      field_height: 2,
+      skin: Skin::fumen(),
+      ..RenderOptions::default()
+    });
+
+    let svg = renderer.render(&RenderContent::new(field));
+
+    assert!(svg.starts_with(
+      r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="20" viewBox="0 0 200 20">"#
+    ));
+    assert!(svg.contains(r##"<rect x="50" y="10" width="9" height="9" fill="#009999"/>"##));
+    assert!(svg.ends_with("</svg>"));
+  }
+}
@@ -10,6 +10,12 @@ use std::io::{Read, Write};
 pub struct Settings {
     pub hub: HubSettings,
     pub solution_finder: SolutionFinderSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub tutor: TutorOptions,
+    #[serde(default)]
+    pub tbp: TbpSettings,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,9 +24,156 @@ pub struct HubSettings {
     pub port: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SolutionFinderSettings {
+    #[serde(default)]
+    pub backend: SolverBackendKind,
+    pub path: Option<String>,
+    pub java_path: Option<String>,
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    pub timeout_sec: Option<u64>,
+    pub max_concurrency: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolverBackendKind {
+    #[default]
+    Sfinder,
+    Native,
+}
+
+// Keys left out of a [cache] table fall back to CacheSettings::default().
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheSettings {
+    pub enabled: bool,
     pub path: Option<String>,
+    pub max_entries: usize,
+    pub max_size_mb: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        CacheSettings {
+            enabled: true,
+            path: None,
+            max_entries: 1000,
+            max_size_mb: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TutorOptions {
+    pub backend: Option<TutorBackendKind>,
+    pub mode: Option<TutorModeOption>,
+    pub opener: Option<OpenerOption>,
+    pub threads: Option<u32>,
+    pub min_nodes: Option<u32>,
+    pub max_nodes: Option<u32>,
+    pub think_ms: Option<u32>,
+    pub think_nodes: Option<u32>,
+    pub pc_loop: Option<PcLoopOption>,
+    pub spawn_rule: Option<SpawnRuleOption>,
+    pub movement_mode: Option<MovementModeOption>,
+    pub weights_preset: Option<WeightsPreset>,
+    pub weights: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TutorBackendKind {
+    ColdClear,
+    Tbp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TutorModeOption {
+    Normal,
+    PcLoop,
+    Opener,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenerOption {
+    Tki,
+    DtCannon,
+    Pco,
+    Mko,
+    Albatross,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PcLoopOption {
+    Off,
+    Fastest,
+    HighestAttack,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnRuleOption {
+    Row19Or20,
+    Row21AndFall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementModeOption {
+    ZeroG,
+    ZeroGComplete,
+    TwentyG,
+    HardDropOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightsPreset {
+    Standard,
+    Fast,
+    Downstack,
+    Tspin,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TbpSettings {
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl TutorOptions {
+    pub fn merge(&self, overrides: &TutorOptions) -> TutorOptions {
+        let weights = match (&self.weights, &overrides.weights) {
+            (Some(base), Some(x)) => {
+                let mut weights = base.clone();
+                weights.extend(x.clone());
+                Some(weights)
+            }
+            (base, x) => x.clone().or_else(|| base.clone()),
+        };
+
+        TutorOptions {
+            backend: overrides.backend.or(self.backend),
+            mode: overrides.mode.or(self.mode),
+            opener: overrides.opener.or(self.opener),
+            threads: overrides.threads.or(self.threads),
+            min_nodes: overrides.min_nodes.or(self.min_nodes),
+            max_nodes: overrides.max_nodes.or(self.max_nodes),
+            think_ms: overrides.think_ms.or(self.think_ms),
+            think_nodes: overrides.think_nodes.or(self.think_nodes),
+            pc_loop: overrides.pc_loop.or(self.pc_loop),
+            spawn_rule: overrides.spawn_rule.or(self.spawn_rule),
+            movement_mode: overrides.movement_mode.or(self.movement_mode),
+            weights_preset: overrides.weights_preset.or(self.weights_preset),
+            weights,
+        }
+    }
 }
 
 impl fmt::Display for Settings {
@@ -28,6 +181,9 @@ impl fmt::Display for Settings {
         writeln!(f, "----------------------------------------")?;
         writeln!(f, "{}", self.hub)?;
         write!(f, "{}", self.solution_finder)?;
+        write!(f, "{}", self.cache)?;
+        write!(f, "{}", self.tutor)?;
+        write!(f, "{}", self.tbp)?;
         writeln!(f, "----------------------------------------")
     }
 }
@@ -43,7 +199,69 @@ impl fmt::Display for HubSettings {
 impl fmt::Display for SolutionFinderSettings {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "[solution_finder]")?;
-        writeln!(f, "  path: {}", self.path.clone().unwrap_or_default())
+        writeln!(f, "  backend: {:?}", self.backend)?;
+        writeln!(f, "  path: {}", self.path.clone().unwrap_or_default())?;
+        writeln!(
+            f,
+            "  java_path: {}",
+            self.java_path.clone().unwrap_or_default()
+        )?;
+        writeln!(f, "  jvm_args: {}", self.jvm_args.join(" "))?;
+        match self.timeout_sec {
+            Some(sec) => writeln!(f, "  timeout_sec: {}", sec),
+            None => writeln!(f, "  timeout_sec: none"),
+        }?;
+        match self.max_concurrency {
+            Some(n) => writeln!(f, "  max_concurrency: {}", n),
+            None => writeln!(f, "  max_concurrency: default"),
+        }
+    }
+}
+
+impl fmt::Display for CacheSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[cache]")?;
+        writeln!(f, "  enabled: {}", self.enabled)?;
+        writeln!(f, "  path: {}", self.path.clone().unwrap_or_default())?;
+        writeln!(f, "  max_entries: {}", self.max_entries)?;
+        writeln!(f, "  max_size_mb: {}", self.max_size_mb)
+    }
+}
+
+impl fmt::Display for TutorOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_default<T: fmt::Debug>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map(|x| format!("{:?}", x))
+                .unwrap_or_else(|| String::from("default"))
+        }
+
+        writeln!(f, "[tutor]")?;
+        writeln!(f, "  backend: {}", or_default(&self.backend))?;
+        writeln!(f, "  mode: {}", or_default(&self.mode))?;
+        writeln!(f, "  opener: {}", or_default(&self.opener))?;
+        writeln!(f, "  threads: {}", or_default(&self.threads))?;
+        writeln!(f, "  min_nodes: {}", or_default(&self.min_nodes))?;
+        writeln!(f, "  max_nodes: {}", or_default(&self.max_nodes))?;
+        writeln!(f, "  think_ms: {}", or_default(&self.think_ms))?;
+        writeln!(f, "  think_nodes: {}", or_default(&self.think_nodes))?;
+        writeln!(f, "  pc_loop: {}", or_default(&self.pc_loop))?;
+        writeln!(f, "  spawn_rule: {}", or_default(&self.spawn_rule))?;
+        writeln!(f, "  movement_mode: {}", or_default(&self.movement_mode))?;
+        writeln!(f, "  weights_preset: {}", or_default(&self.weights_preset))?;
+        match &self.weights {
+            Some(weights) => writeln!(f, "  weights: {}", serde_json::Value::from(weights.clone())),
+            None => writeln!(f, "  weights: default"),
+        }
+    }
+}
+
+impl fmt::Display for TbpSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[tbp]")?;
+        writeln!(f, "  command: {}", self.command.clone().unwrap_or_default())?;
+        writeln!(f, "  args: {}", self.args.join(" "))
     }
 }
 
@@ -68,3 +286,28 @@ impl Settings {
             .map_err(|e| format!("{:#}", e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_cache_table() {
+        let toml = r#"
+[hub]
+host = "127.0.0.1"
+port = 8080
+
+[solution_finder]
+
+[cache]
+enabled = false
+"#;
+
+        let settings = from_str::<Settings>(toml).unwrap();
+        assert!(!settings.cache.enabled);
+        assert_eq!(settings.cache.path, None);
+        assert_eq!(settings.cache.max_entries, 1000);
+        assert_eq!(settings.cache.max_size_mb, 100);
+    }
+}
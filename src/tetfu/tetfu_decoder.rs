@@ -1,10 +1,10 @@
+use crate::coordinates::from_fumen_cell;
 use crate::tetfu::core::Tetsimu2Content;
 use crate::tetfu::core::ASCII_TABLE;
 use crate::tetfu::core::ENCODE_TABLE;
 use crate::tetfu::core::MAX_TETFU_FIELD_HEIGHT;
 use crate::tetfu::core::MAX_TETFU_FIELD_SIZE_EX;
 use crate::tetfu::core::MAX_TETFU_FIELD_WIDTH;
-use crate::tetsimu2::core::FieldCellValue;
 use crate::tetsimu2::field::Field;
 use substring::Substring;
 
@@ -15,21 +15,6 @@ impl TetfuDecoder {
     TetfuDecoder {}
   }
 
-  fn convert_cell(&self, cell: i32) -> Option<FieldCellValue> {
-    match cell {
-      0 => Some(FieldCellValue::None),
-      1 => Some(FieldCellValue::I),
-      6 => Some(FieldCellValue::J),
-      2 => Some(FieldCellValue::L),
-      3 => Some(FieldCellValue::O),
-      7 => Some(FieldCellValue::S),
-      5 => Some(FieldCellValue::T),
-      4 => Some(FieldCellValue::Z),
-      8 => Some(FieldCellValue::Garbage),
-      _ => None,
-    }
-  }
-
   pub fn decode(&self, tetfu_parameter: String) -> Result<Tetsimu2Content, String> {
     let mut field = Field::new();
 
@@ -56,7 +41,7 @@ impl TetfuDecoder {
       let tmp = v1 + v2 * 64;
       let repeat_cell_count = tmp % MAX_TETFU_FIELD_SIZE_EX;
       let cell = (tmp / MAX_TETFU_FIELD_SIZE_EX) % 17 - 8;
-      let cell = self.convert_cell(cell).ok_or(format!(
+      let cell = from_fumen_cell(cell).ok_or(format!(
         "Cannot convert cell value({}) to FieldCellValue.",
         cell
       ))?;
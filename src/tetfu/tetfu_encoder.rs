@@ -1,3 +1,4 @@
+use crate::coordinates::fumen_cell;
 use crate::tetfu::core::Tetsimu2Content;
 use crate::tetfu::core::ASCII_TABLE;
 use crate::tetfu::core::ENCODE_TABLE;
@@ -59,7 +60,7 @@ impl TetfuEncoder {
 
   fn encode_for_field(&self, field: &Field, enc: &mut Vec<i32>) -> i32 {
     let mut repeat_cell_count = 0;
-    let mut prev_cell = self.convert_cell(field.get_cell(0, MAX_TETFU_FIELD_HEIGHT - 1)) + 8;
+    let mut prev_cell = fumen_cell(field.get_cell(0, MAX_TETFU_FIELD_HEIGHT - 1)) + 8;
 
     for p in 1..(MAX_TETFU_FIELD_SIZE_EX) {
       let cell = if p >= MAX_TETFU_FIELD_SIZE {
@@ -67,7 +68,7 @@ impl TetfuEncoder {
       } else {
         let x = p % MAX_FIELD_WIDTH;
         let y = (MAX_TETFU_FIELD_HEIGHT - 1) - (p / MAX_FIELD_WIDTH); // 0 ～ MAX_TETFU_FIELD_HEIGHT
-        self.convert_cell(field.get_cell(x, y)) + 8
+        fumen_cell(field.get_cell(x, y)) + 8
       };
       if cell != prev_cell {
         let tmp = prev_cell * MAX_TETFU_FIELD_SIZE_EX + repeat_cell_count;
@@ -130,20 +131,6 @@ impl TetfuEncoder {
       i += 4;
     }
   }
-
-  fn convert_cell(&self, cell: FieldCellValue) -> i32 {
-    match cell {
-      FieldCellValue::None => 0,
-      FieldCellValue::I => 1,
-      FieldCellValue::J => 6,
-      FieldCellValue::L => 2,
-      FieldCellValue::O => 3,
-      FieldCellValue::S => 7,
-      FieldCellValue::T => 5,
-      FieldCellValue::Z => 4,
-      FieldCellValue::Garbage => 8,
-    }
-  }
 }
 
 fn encode(s: &str) -> String {
@@ -1,3 +1,4 @@
+pub mod attack;
 pub mod core;
 pub mod current_tetromino;
 pub mod field;
@@ -5,5 +6,7 @@ pub mod field_conductor;
 pub mod fixed_next_generator;
 pub mod hold;
 pub mod next_generator;
+pub mod placements;
 pub mod router;
 pub mod tetromino;
+pub mod url;
@@ -0,0 +1,109 @@
+// Guideline garbage lines sent for the n-th consecutive clear, counting from 0.
+const COMBO_ATTACK: [u32; 11] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5];
+const BTB_ATTACK: u32 = 1;
+const PC_ATTACK: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineClear {
+  pub lines: u32,
+  pub is_tspin: bool,
+  pub is_pc: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AttackState {
+  pub combo: u32,
+  pub is_btb: bool,
+}
+
+impl AttackState {
+  // Lines sent by a placement, updating the combo and back-to-back for the next one.
+  pub fn attack(&mut self, clear: &LineClear) -> u32 {
+    if clear.lines == 0 {
+      self.combo = 0;
+      return 0;
+    }
+
+    let base = if clear.is_tspin {
+      clear.lines * 2
+    } else {
+      match clear.lines {
+        1 => 0,
+        2 => 1,
+        3 => 2,
+        _ => 4,
+      }
+    };
+
+    let is_difficult = clear.is_tspin || clear.lines >= 4;
+    let btb = if is_difficult && self.is_btb {
+      BTB_ATTACK
+    } else {
+      0
+    };
+    let combo = COMBO_ATTACK[(self.combo as usize).min(COMBO_ATTACK.len() - 1)];
+    let pc = if clear.is_pc { PC_ATTACK } else { 0 };
+
+    self.is_btb = is_difficult;
+    self.combo += 1;
+    base + btb + combo + pc
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn clear(lines: u32, is_tspin: bool) -> LineClear {
+    LineClear {
+      lines,
+      is_tspin,
+      is_pc: false,
+    }
+  }
+
+  #[test]
+  fn attack() {
+    let cases = [
+      (clear(1, false), 0),
+      (clear(2, false), 1),
+      (clear(3, false), 2),
+      (clear(4, false), 4),
+      (clear(1, true), 2),
+      (clear(2, true), 4),
+      (clear(3, true), 6),
+    ];
+    for (clear, expected) in &cases {
+      assert_eq!(
+        AttackState::default().attack(clear),
+        *expected,
+        "{:?}",
+        clear
+      );
+    }
+  }
+
+  #[test]
+  fn attack_with_btb_and_combo() {
+    let mut state = AttackState::default();
+    assert_eq!(state.attack(&clear(4, false)), 4);
+    assert_eq!(state.attack(&clear(2, true)), 4 + 1 + 1);
+    assert_eq!(state.attack(&clear(1, false)), 1);
+    assert_eq!(state.is_btb, false);
+    assert_eq!(state.attack(&clear(4, false)), 4 + 2);
+
+    assert_eq!(state.attack(&clear(0, false)), 0);
+    assert_eq!(state.combo, 0);
+    assert_eq!(state.is_btb, true);
+  }
+
+  #[test]
+  fn attack_with_pc() {
+    let pc = LineClear {
+      lines: 2,
+      is_tspin: false,
+      is_pc: true,
+    };
+    assert_eq!(AttackState::default().attack(&pc), 1 + 10);
+  }
+}
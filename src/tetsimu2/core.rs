@@ -18,7 +18,7 @@ pub enum Direction {
   Right,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy, FromPrimitive, PartialOrd, Ord)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, FromPrimitive, PartialOrd, Ord, Hash)]
 pub enum Tetromino {
   I = 1,
   J = 2,
@@ -48,6 +48,66 @@ impl TryFrom<char> for Tetromino {
   }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum DropType {
+  SoftDrop = 0,
+  HardDrop = 1,
+  OneHundredEighty = 2,
+  TSoftDrop = 3,
+  AnyTSpin = 4,
+  Tss = 5,
+  Tsd = 6,
+  Tst = 7,
+}
+
+impl DropType {
+  pub fn can_soft_drop(&self, r#type: Tetromino) -> bool {
+    match self {
+      DropType::HardDrop => false,
+      DropType::TSoftDrop => r#type == Tetromino::T,
+      _ => true,
+    }
+  }
+
+  pub fn can_turn_180(&self) -> bool {
+    *self == DropType::OneHundredEighty
+  }
+
+  pub fn required_tspin_lines(&self, r#type: Tetromino) -> Option<u32> {
+    if r#type != Tetromino::T {
+      return None;
+    }
+
+    match self {
+      DropType::AnyTSpin => Some(0),
+      DropType::Tss => Some(1),
+      DropType::Tsd => Some(2),
+      DropType::Tst => Some(3),
+      _ => None,
+    }
+  }
+}
+
+impl TryFrom<u8> for DropType {
+  type Error = String;
+
+  fn try_from(n: u8) -> Result<Self, Self::Error> {
+    let t = match n {
+      0 => DropType::SoftDrop,
+      1 => DropType::HardDrop,
+      2 => DropType::OneHundredEighty,
+      3 => DropType::TSoftDrop,
+      4 => DropType::AnyTSpin,
+      5 => DropType::Tss,
+      6 => DropType::Tsd,
+      7 => DropType::Tst,
+      _ => return Err(format!("Cannot convert from '{}' to DropType", n)),
+    };
+
+    Ok(t)
+  }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Step {
   pub r#type: Tetromino,
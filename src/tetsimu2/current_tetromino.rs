@@ -63,6 +63,18 @@ impl CurrentTetromino {
     }
   }
 
+  pub fn srss_180(&self) -> &'static [XY] {
+    match self.r#type {
+      Tetromino::I => TetrominoI::srss_180(self.direction),
+      Tetromino::J => TetrominoJ::srss_180(self.direction),
+      Tetromino::L => TetrominoL::srss_180(self.direction),
+      Tetromino::O => TetrominoO::srss_180(self.direction),
+      Tetromino::S => TetrominoS::srss_180(self.direction),
+      Tetromino::T => TetrominoT::srss_180(self.direction),
+      Tetromino::Z => TetrominoZ::srss_180(self.direction),
+    }
+  }
+
   pub fn drop_to_bottom(&mut self, field: &Field) {
     let mut current = self.clone();
     loop {
@@ -175,6 +187,32 @@ impl CurrentTetromino {
     }
   }
 
+  pub fn turn_180(&mut self, field: &Field) -> bool {
+    let mut current = self.clone();
+    current.direction = FromPrimitive::from_i32((self.direction as i32 + 2) % 4).unwrap();
+
+    if field.is_overlapped(&current) {
+      let srs = self.srss_180();
+      let org_current = current;
+      for &(dx, dy) in srs {
+        let mut current = org_current.clone();
+        current.x += dx;
+        current.y += dy;
+
+        if !field.is_overlapped(&current) {
+          self.x = current.x;
+          self.y = current.y;
+          self.direction = current.direction;
+          return true;
+        }
+      }
+      false
+    } else {
+      self.direction = current.direction;
+      true
+    }
+  }
+
   pub fn flip(&mut self) -> bool {
     if self.r#type == Tetromino::J {
       return false;
@@ -425,6 +463,89 @@ mod tests {
     assert_eq!(current.y, 6);
   }
 
+  #[test]
+  fn turn_180() {
+    let field = Field::new();
+    let mut current = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 4,
+      y: 5,
+    };
+
+    assert_eq!(current.turn_180(&field), true);
+    assert_eq!(current.direction, Direction::Down);
+    assert_eq!(current.turn_180(&field), true);
+    assert_eq!(current.direction, Direction::Up);
+  }
+
+  #[test]
+  fn turn_180_with_kicks() {
+    #[rustfmt::skip]
+    let field = make_field(
+      &format!("{}{}{}",
+          "NNNNNNNNNN",
+          "NNNNNNNNNN",
+          "GGGGGGGGGN"));
+
+    let mut current = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 4,
+      y: 1,
+    };
+
+    assert_eq!(current.turn_180(&field), true);
+    assert_eq!(current.direction, Direction::Down);
+    assert_eq!((current.x, current.y), (4, 2));
+  }
+
+  #[test]
+  fn turn_180_kicks_sideways_first() {
+    #[rustfmt::skip]
+    let field = make_field(
+      &format!("{}{}{}",
+          "NNNNNNNNNN",
+          "NNNNNNNNNN",
+          "GGGGNGGGGG"));
+
+    let mut current = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 3,
+      y: 1,
+    };
+
+    assert_eq!(current.turn_180(&field), true);
+    assert_eq!(current.direction, Direction::Down);
+    assert_eq!((current.x, current.y), (4, 1));
+  }
+
+  #[test]
+  fn turn_180_without_room() {
+    #[rustfmt::skip]
+    let field = make_field(
+      &format!("{}{}{}{}{}{}{}",
+          "GGGGGGGGGG",
+          "GGGGGGGGGG",
+          "GGGGGGGGGG",
+          "GGGNNNGGGG",
+          "GGGGNGGGGG",
+          "GGGGGGGGGG",
+          "GGGGGGGGGG"));
+
+    let mut current = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Down,
+      x: 4,
+      y: 3,
+    };
+
+    assert_eq!(current.turn_180(&field), false);
+    assert_eq!(current.direction, Direction::Down);
+    assert_eq!((current.x, current.y), (4, 3));
+  }
+
   #[parameterized(r#type = {
     Tetromino::J, Tetromino::L, Tetromino::O, Tetromino::T
   }, expected = {
@@ -108,6 +108,29 @@ impl Field {
     self.data = after_data;
     cleared_line
   }
+
+  // Raises the field by `lines` garbage rows with a hole at `hole_x`. Returns false when blocks
+  // were pushed out of the top.
+  pub fn add_garbage(&mut self, lines: u32, hole_x: i32) -> bool {
+    let lines = lines as i32;
+    let is_pushed_out = (MAX_FIELD_HEIGHT - lines.min(MAX_FIELD_HEIGHT)..MAX_FIELD_HEIGHT)
+      .any(|y| (0..MAX_FIELD_WIDTH).any(|x| self.get_cell(x, y) != FieldCellValue::None));
+
+    for y in (0..MAX_FIELD_HEIGHT).rev() {
+      for x in 0..MAX_FIELD_WIDTH {
+        let cell = if y >= lines {
+          self.get_cell(x, y - lines)
+        } else if x == hole_x {
+          FieldCellValue::None
+        } else {
+          FieldCellValue::Garbage
+        };
+        self.set_cell(x, y, cell);
+      }
+    }
+
+    !is_pushed_out
+  }
 }
 
 impl Debug for Field {
@@ -173,4 +196,20 @@ mod tests {
       "GGGGGGGGNG",
     )));
   }
+
+  #[test]
+  fn add_garbage() {
+    let mut field = make_field("NNNNIIIINN");
+
+    assert_eq!(field.add_garbage(2, 3), true);
+    #[rustfmt::skip]
+    assert_eq!(field, make_field(&format!("{}{}{}",
+      "NNNNIIIINN",
+      "GGGNGGGGGG",
+      "GGGNGGGGGG",
+    )));
+
+    field.set_cell(0, MAX_FIELD_HEIGHT - 1, FieldCellValue::I);
+    assert_eq!(field.add_garbage(1, 0), false);
+  }
 }
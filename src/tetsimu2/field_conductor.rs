@@ -4,10 +4,12 @@ use crate::tetsimu2::current_tetromino::CurrentTetromino;
 use crate::tetsimu2::field::Field;
 use crate::tetsimu2::hold::Hold;
 use crate::tetsimu2::next_generator::NextGenerator;
+use std::collections::VecDeque;
 
 pub struct FieldConductor {
   current: CurrentTetromino,
   next_generator: Box<dyn NextGenerator>,
+  nexts: VecDeque<Tetromino>,
   hold: Hold,
   is_dead: bool,
   field: Field,
@@ -28,9 +30,30 @@ impl FieldConductor {
       field: Field::new(),
       hold: Hold::new(),
       next_generator: gen,
+      nexts: VecDeque::new(),
     }
   }
 
+  pub fn current(&self) -> &CurrentTetromino {
+    &self.current
+  }
+
+  pub fn field(&self) -> &Field {
+    &self.field
+  }
+
+  // Up to `count` pieces after the current one, as far as the generator can tell.
+  pub fn nexts(&mut self, count: usize) -> Vec<Tetromino> {
+    while self.nexts.len() < count {
+      match self.next_generator.next() {
+        Some(x) => self.nexts.push_back(x),
+        None => break,
+      }
+    }
+
+    self.nexts.iter().take(count).copied().collect()
+  }
+
   pub fn can_hold(&self) -> bool {
     self.hold.can_hold()
   }
@@ -43,20 +66,29 @@ impl FieldConductor {
     self.is_dead
   }
 
-  pub fn hard_drop(&mut self) {
+  // Returns the number of cleared lines.
+  pub fn hard_drop(&mut self) -> u32 {
     self.current.drop_to_bottom(&self.field);
     self.field.settle_tetromino(&self.current);
+    self.hold.make_holdable();
 
     if !self.field.is_in_inner_field(&self.current) {
       self.is_dead = true;
-      return;
+      return 0;
     }
 
-    self.field.clear_lines();
+    let cleared_lines = self.field.clear_lines();
 
     if !self.proceed_next() {
       self.is_dead = true;
     }
+    cleared_lines
+  }
+
+  pub fn receive_garbage(&mut self, lines: u32, hole_x: i32) {
+    if !self.field.add_garbage(lines, hole_x) || self.field.is_overlapped(&self.current) {
+      self.is_dead = true;
+    }
   }
 
   pub fn soft_drop(&mut self) -> bool {
@@ -87,8 +119,12 @@ impl FieldConductor {
   }
 
   fn proceed_next(&mut self) -> bool {
+    let r#type = match self.nexts.pop_front() {
+      Some(x) => x,
+      None => self.next_generator.next().expect("Cannot generate next."),
+    };
     let mut current = CurrentTetromino {
-      r#type: self.next_generator.next().expect("Cannot generate next."),
+      r#type,
       direction: Direction::Up,
       x: 4,
       y: 19,
@@ -111,6 +147,7 @@ impl FieldConductor {
   pub fn reset(&mut self, gen: Box<dyn NextGenerator>, hold: Hold, field: Field) {
     self.field = field;
     self.next_generator = gen;
+    self.nexts.clear();
     self.hold = hold;
 
     self.proceed_next();
@@ -234,4 +271,41 @@ mod tests {
     assert_eq!(conductor.can_hold(), false);
     assert_eq!(conductor.holded(), Some(Tetromino::I));
   }
+
+  #[test]
+  fn hold_after_hard_drop() {
+    let gen = FixedNextGenerator::new(vec![Tetromino::I, Tetromino::J, Tetromino::L]);
+    let mut conductor = FieldConductor::new(Box::new(gen));
+    assert_eq!(conductor.hold(), true);
+    assert_eq!(conductor.hold(), false);
+
+    conductor.hard_drop();
+    assert_eq!(conductor.hold(), true);
+    assert_eq!(conductor.current().r#type, Tetromino::I);
+    assert_eq!(conductor.holded(), Some(Tetromino::L));
+  }
+
+  #[test]
+  fn nexts() {
+    let gen = FixedNextGenerator::new(vec![Tetromino::I, Tetromino::J, Tetromino::L]);
+    let mut conductor = FieldConductor::new(Box::new(gen));
+    assert_eq!(conductor.nexts(1), vec![Tetromino::J]);
+    assert_eq!(conductor.nexts(5), vec![Tetromino::J, Tetromino::L]);
+
+    assert_eq!(conductor.hard_drop(), 0);
+    assert_eq!(conductor.current().r#type, Tetromino::J);
+    assert_eq!(conductor.nexts(5), vec![Tetromino::L]);
+  }
+
+  #[test]
+  fn receive_garbage() {
+    let gen = FixedNextGenerator::new(vec![Tetromino::I, Tetromino::J]);
+    let mut conductor = FieldConductor::new(Box::new(gen));
+    conductor.receive_garbage(1, 0);
+    assert_eq!(conductor.field(), &make_field("NGGGGGGGGG"));
+    assert_eq!(conductor.is_dead(), false);
+
+    conductor.receive_garbage(19, 0);
+    assert_eq!(conductor.is_dead(), true);
+  }
 }
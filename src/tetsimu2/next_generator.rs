@@ -1,5 +1,7 @@
 use crate::tetsimu2::core::Tetromino;
+use rand::rngs::StdRng;
 use rand::Rng;
+use rand::SeedableRng;
 
 pub trait NextGenerator {
   fn next(&mut self) -> Option<Tetromino>;
@@ -8,11 +10,23 @@ pub trait NextGenerator {
 
 pub struct RandomNextGenerator {
   bag: Vec<Tetromino>,
+  rng: StdRng,
 }
 
 impl RandomNextGenerator {
   pub fn new(bag: Vec<Tetromino>) -> RandomNextGenerator {
-    RandomNextGenerator { bag }
+    RandomNextGenerator {
+      bag,
+      rng: StdRng::from_entropy(),
+    }
+  }
+
+  // Generates the same sequence for the same seed.
+  pub fn with_seed(bag: Vec<Tetromino>, seed: u64) -> RandomNextGenerator {
+    RandomNextGenerator {
+      bag,
+      rng: StdRng::seed_from_u64(seed),
+    }
   }
 }
 
@@ -28,7 +42,7 @@ impl NextGenerator for RandomNextGenerator {
       self.bag.push(Tetromino::Z);
     }
 
-    let p = rand::thread_rng().gen_range(0..self.bag.len());
+    let p = self.rng.gen_range(0..self.bag.len());
     let tetromino = self.bag.remove(p);
     Some(tetromino)
   }
@@ -68,4 +82,15 @@ mod tests {
       )
     }
   }
+
+  #[test]
+  fn with_seed() {
+    let sequence = |seed| {
+      let mut gen = RandomNextGenerator::with_seed(Vec::new(), seed);
+      (0..21).map(|_| gen.next().unwrap()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(sequence(1), sequence(1));
+    assert_ne!(sequence(1), sequence(2));
+  }
 }
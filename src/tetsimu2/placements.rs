@@ -0,0 +1,278 @@
+use crate::tetsimu2::core::Direction;
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
+use crate::tetsimu2::core::MAX_FIELD_WIDTH;
+use crate::tetsimu2::current_tetromino::CurrentTetromino;
+use crate::tetsimu2::field::Field;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlayState<'a> {
+  pub field: &'a Field,
+  pub queue: &'a [Tetromino],
+  pub hold: Option<Tetromino>,
+  pub can_hold: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferredPlacement {
+  pub piece: CurrentTetromino,
+  pub hold: bool,
+  pub cleared_lines: u32,
+}
+
+// Grounded positions below `height`, one for each distinct set of cells.
+pub fn placements(field: &Field, r#type: Tetromino, height: i32) -> Vec<CurrentTetromino> {
+  let directions = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+  ];
+
+  let mut searched = HashSet::new();
+  let mut placements = vec![];
+  for &direction in &directions {
+    for x in -2..MAX_FIELD_WIDTH + 2 {
+      for y in -2..height + 2 {
+        let piece = CurrentTetromino {
+          r#type,
+          direction,
+          x,
+          y,
+        };
+        let mut blocks = piece.blocks();
+        if blocks.iter().any(|&(_, y)| y >= height) || field.is_overlapped(&piece) {
+          continue;
+        }
+
+        let below = CurrentTetromino {
+          y: y - 1,
+          ..piece.clone()
+        };
+        if !field.is_overlapped(&below) {
+          continue;
+        }
+
+        blocks.sort();
+        if searched.insert(blocks) {
+          placements.push(piece);
+        }
+      }
+    }
+  }
+
+  placements
+}
+
+// Works out the placement that turned `prev` into `next`. Cells are compared by occupancy only,
+// since a piece may land next to cells of the same colour. When several placements leave the same
+// cells, the one whose remaining cells have the piece's colour wins.
+pub fn infer_placement(prev: &PlayState, next: &PlayState) -> Option<InferredPlacement> {
+  let holds: &[bool] = if prev.can_hold {
+    &[false, true]
+  } else {
+    &[false]
+  };
+
+  let mut best: Option<(usize, InferredPlacement)> = None;
+  for &hold in holds {
+    let (r#type, hold_after, queue_after) = match take_piece(prev, hold) {
+      Some(x) => x,
+      None => continue,
+    };
+    if hold_after != next.hold || !next.queue.starts_with(queue_after) {
+      continue;
+    }
+
+    for piece in placements(prev.field, r#type, MAX_FIELD_HEIGHT) {
+      let mut field = prev.field.clone();
+      field.settle_tetromino(&piece);
+      let full_rows = full_rows(&field);
+      let cleared_lines = field.clear_lines();
+      if !is_same_occupancy(&field, next.field) {
+        continue;
+      }
+
+      let colours = colour_matches(&piece, &full_rows, next.field);
+      let is_better = match &best {
+        Some((x, _)) => colours > *x,
+        None => true,
+      };
+      if is_better {
+        best = Some((
+          colours,
+          InferredPlacement {
+            piece,
+            hold,
+            cleared_lines,
+          },
+        ));
+      }
+    }
+  }
+
+  best.map(|(_, x)| x)
+}
+
+fn take_piece<'a>(
+  state: &PlayState<'a>,
+  hold: bool,
+) -> Option<(Tetromino, Option<Tetromino>, &'a [Tetromino])> {
+  let (&current, rest) = state.queue.split_first()?;
+  if !hold {
+    return Some((current, state.hold, rest));
+  }
+
+  match state.hold {
+    Some(held) => Some((held, Some(current), rest)),
+    None => {
+      let (&next, rest) = rest.split_first()?;
+      Some((next, Some(current), rest))
+    }
+  }
+}
+
+fn full_rows(field: &Field) -> Vec<i32> {
+  (0..MAX_FIELD_HEIGHT)
+    .filter(|&y| (0..MAX_FIELD_WIDTH).all(|x| field.get_cell(x, y) != FieldCellValue::None))
+    .collect()
+}
+
+fn is_same_occupancy(a: &Field, b: &Field) -> bool {
+  a.data
+    .iter()
+    .zip(b.data.iter())
+    .all(|(x, y)| (*x == FieldCellValue::None) == (*y == FieldCellValue::None))
+}
+
+fn colour_matches(piece: &CurrentTetromino, full_rows: &[i32], field: &Field) -> usize {
+  piece
+    .blocks()
+    .iter()
+    .filter(|(_, y)| !full_rows.contains(y))
+    .filter(|&&(x, y)| {
+      let shift = full_rows.iter().filter(|&&row| row < y).count() as i32;
+      field.get_cell(x, y - shift) == FieldCellValue::from(piece.r#type)
+    })
+    .count()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+
+  fn infer(
+    prev: (&[&str], &[Tetromino], Option<Tetromino>),
+    next: (&[&str], &[Tetromino], Option<Tetromino>),
+  ) -> Option<InferredPlacement> {
+    let prev_field = make_field(&prev.0.concat());
+    let next_field = make_field(&next.0.concat());
+    infer_placement(
+      &PlayState {
+        field: &prev_field,
+        queue: prev.1,
+        hold: prev.2,
+        can_hold: true,
+      },
+      &PlayState {
+        field: &next_field,
+        queue: next.1,
+        hold: next.2,
+        can_hold: true,
+      },
+    )
+  }
+
+  fn placement(r#type: Tetromino, direction: Direction, x: i32, y: i32) -> CurrentTetromino {
+    CurrentTetromino {
+      r#type,
+      direction,
+      x,
+      y,
+    }
+  }
+
+  #[test]
+  fn placements() {
+    let field = Field::new();
+    assert_eq!(
+      super::placements(&field, Tetromino::T, MAX_FIELD_HEIGHT).len(),
+      34
+    );
+    assert_eq!(
+      super::placements(&field, Tetromino::O, MAX_FIELD_HEIGHT).len(),
+      9
+    );
+    assert_eq!(
+      super::placements(&field, Tetromino::I, MAX_FIELD_HEIGHT).len(),
+      17
+    );
+  }
+
+  #[test]
+  fn infer_placement_without_line_clear() {
+    use Tetromino::*;
+
+    #[rustfmt::skip]
+    let actual = infer(
+      (&[
+        "OONNNNNNNN",
+        "OONNNNNNNN",
+      ], &[O, T, S], None),
+      (&[
+        "OOOONNNNNN",
+        "OOOONNNNNN",
+      ], &[T, S, Z], None),
+    );
+    assert_eq!(
+      actual,
+      Some(InferredPlacement {
+        piece: placement(O, Direction::Up, 2, 0),
+        hold: false,
+        cleared_lines: 0,
+      })
+    );
+  }
+
+  #[test]
+  fn infer_placement_with_line_clear_and_hold() {
+    use Tetromino::*;
+
+    #[rustfmt::skip]
+    let actual = infer(
+      (&[
+        "NNNNNNSNNN",
+        "GGGGGNNNGG",
+        "GGGGGGNGGG",
+      ], &[S, T, Z], None),
+      (&[
+        "NNNNNNSNNN",
+      ], &[Z, L], Some(S)),
+    );
+    assert_eq!(
+      actual,
+      Some(InferredPlacement {
+        piece: placement(T, Direction::Down, 6, 1),
+        hold: true,
+        cleared_lines: 2,
+      })
+    );
+
+    #[rustfmt::skip]
+    let actual = infer(
+      (&[
+        "GGGGGNNNGG",
+        "GGGGGGNGGG",
+      ], &[S, T, Z], Some(I)),
+      (&[
+        "GGGGGNNNGG",
+        "GGGGGGNGGG",
+        "GGGGGGGGGN",
+      ], &[T, Z, L], Some(I)),
+    );
+    assert_eq!(actual, None);
+  }
+}
@@ -1,3 +1,5 @@
+use crate::tetsimu2::core::DropType;
+use crate::tetsimu2::core::FieldCellValue;
 use crate::tetsimu2::core::MAX_FIELD_HEIGHT;
 use crate::tetsimu2::current_tetromino::CurrentTetromino;
 use crate::tetsimu2::field::Field;
@@ -15,6 +17,7 @@ pub enum SearchRouteAction {
   TurnLeft = 3,
   TurnRight = 4,
   SoftDrop = 5,
+  Turn180 = 6,
 }
 
 struct StackItem {
@@ -26,6 +29,7 @@ struct RouteSearchContext<'a> {
   queue: VecDeque<StackItem>,
   searched: HashSet<i32>,
   goal: &'a CurrentTetromino,
+  requires_tspin: bool,
 }
 
 impl<'a> RouteSearcher<'a> {
@@ -34,6 +38,25 @@ impl<'a> RouteSearcher<'a> {
     start: CurrentTetromino,
     goal: &CurrentTetromino,
   ) -> Option<Vec<SearchRouteAction>> {
+    self.search_route_with_drop_type(start, goal, DropType::SoftDrop)
+  }
+
+  pub fn search_route_with_drop_type(
+    &self,
+    start: CurrentTetromino,
+    goal: &CurrentTetromino,
+    drop_type: DropType,
+  ) -> Option<Vec<SearchRouteAction>> {
+    let requires_tspin = match drop_type.required_tspin_lines(goal.r#type) {
+      Some(lines) => {
+        if !self.is_tspin_goal(goal, lines) {
+          return None;
+        }
+        true
+      }
+      None => false,
+    };
+
     let mut context = RouteSearchContext {
       queue: VecDeque::from(vec![StackItem {
         start,
@@ -41,11 +64,14 @@ impl<'a> RouteSearcher<'a> {
       }]),
       searched: HashSet::new(),
       goal,
+      requires_tspin,
     };
 
     while let Some(item) = context.queue.pop_front() {
-      if let Some(route_actions) = self.search_with_hard_drop(&context, &item) {
-        return Some(route_actions);
+      if !context.requires_tspin {
+        if let Some(route_actions) = self.search_with_hard_drop(&context, &item) {
+          return Some(route_actions);
+        }
       }
 
       if let Some(route_actions) = self.search_with_turn_left(&mut context, &item) {
@@ -56,6 +82,12 @@ impl<'a> RouteSearcher<'a> {
         return Some(route_actions);
       }
 
+      if drop_type.can_turn_180() {
+        if let Some(route_actions) = self.search_with_turn_180(&mut context, &item) {
+          return Some(route_actions);
+        }
+      }
+
       if let Some(route_actions) = self.search_with_move_left(&mut context, &item) {
         return Some(route_actions);
       }
@@ -64,14 +96,30 @@ impl<'a> RouteSearcher<'a> {
         return Some(route_actions);
       }
 
-      if let Some(route_actions) = self.search_with_soft_drop(&mut context, &item) {
-        return Some(route_actions);
+      if drop_type.can_soft_drop(goal.r#type) {
+        if let Some(route_actions) = self.search_with_soft_drop(&mut context, &item) {
+          return Some(route_actions);
+        }
       }
     }
 
     None
   }
 
+  fn is_tspin_goal(&self, goal: &CurrentTetromino, required_lines: u32) -> bool {
+    let filled_corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+      .iter()
+      .filter(|(dx, dy)| self.field.get_cell(goal.x + dx, goal.y + dy) != FieldCellValue::None)
+      .count();
+    if filled_corners < 3 {
+      return false;
+    }
+
+    let mut field = self.field.clone();
+    field.settle_tetromino(goal);
+    field.clear_lines() >= required_lines
+  }
+
   fn search_with_hard_drop(
     &self,
     context: &RouteSearchContext,
@@ -114,6 +162,19 @@ impl<'a> RouteSearcher<'a> {
     )
   }
 
+  fn search_with_turn_180(
+    &self,
+    context: &mut RouteSearchContext,
+    item: &StackItem,
+  ) -> Option<Vec<SearchRouteAction>> {
+    self.serch_with(
+      context,
+      item,
+      |current| current.turn_180(&self.field),
+      SearchRouteAction::Turn180,
+    )
+  }
+
   fn search_with_move_left(
     &self,
     context: &mut RouteSearchContext,
@@ -166,10 +227,15 @@ impl<'a> RouteSearcher<'a> {
       return None;
     }
 
+    let is_turned = matches!(
+      action,
+      SearchRouteAction::TurnLeft | SearchRouteAction::TurnRight | SearchRouteAction::Turn180
+    );
+
     let mut route_actions = item.route_actions.clone();
     route_actions.push(action);
 
-    if current == *context.goal {
+    if current == *context.goal && (is_turned || !context.requires_tspin) {
       Some(route_actions)
     } else {
       let value = self.calculate_current_value(&current);
@@ -386,4 +452,99 @@ mod tests {
       ])
     );
   }
+
+  #[test]
+  fn search_route_with_harddrop_should_not_use_softdrop() {
+    #[rustfmt::skip]
+    let field = make_field(
+      &format!("{}{}{}{}{}",
+        "NNNNNNNNNN",
+        "NNGGGGGGGG",
+        "NNNNNNNNNN",
+        "NNNNNNNNNN",
+        "NNNNNNNNNN"));
+
+    let start = CurrentTetromino {
+      r#type: Tetromino::O,
+      direction: Direction::Up,
+      x: 4,
+      y: 5,
+    };
+
+    let goal = CurrentTetromino {
+      r#type: Tetromino::O,
+      direction: Direction::Up,
+      x: 4,
+      y: 0,
+    };
+
+    let route_searcher = RouteSearcher { field: &field };
+    assert_eq!(
+      route_searcher.search_route_with_drop_type(start.clone(), &goal, DropType::HardDrop),
+      None
+    );
+    assert_eq!(
+      route_searcher.search_route_with_drop_type(start.clone(), &goal, DropType::TSoftDrop),
+      None
+    );
+    assert_eq!(
+      route_searcher
+        .search_route_with_drop_type(start, &goal, DropType::OneHundredEighty)
+        .is_some(),
+      true
+    );
+  }
+
+  #[test]
+  fn search_route_with_tspin_drop_types() {
+    #[rustfmt::skip]
+    let field = make_field(
+      &format!("{}{}{}{}{}{}",
+        "NNNNNNNNNN",
+        "NNNNNNNNNN",
+        "NNNNNNNNNN",
+        "GGGGNNNNNN",
+        "GGGNNNGGGG",
+        "GGGGNGGGGG"));
+
+    let start = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 4,
+      y: 4,
+    };
+
+    let goal = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Down,
+      x: 4,
+      y: 1,
+    };
+
+    let route_searcher = RouteSearcher { field: &field };
+    let route = route_searcher
+      .search_route_with_drop_type(start.clone(), &goal, DropType::Tsd)
+      .unwrap();
+    assert_eq!(
+      route.last() == Some(&SearchRouteAction::TurnLeft)
+        || route.last() == Some(&SearchRouteAction::TurnRight),
+      true
+    );
+
+    assert_eq!(
+      route_searcher.search_route_with_drop_type(start.clone(), &goal, DropType::Tst),
+      None
+    );
+
+    let flat_goal = CurrentTetromino {
+      r#type: Tetromino::T,
+      direction: Direction::Up,
+      x: 6,
+      y: 2,
+    };
+    assert_eq!(
+      route_searcher.search_route_with_drop_type(start, &flat_goal, DropType::AnyTSpin),
+      None
+    );
+  }
 }
@@ -17,6 +17,15 @@ const SRSS_RIGHT: [XY; 16] = [
   ( 1, 0), ( 1, -1), (0,  2), ( 1,  2),
 ];
 
+// Nullpomino's 180 kicks, which sfinder also uses for its '@nullpomino180' preset.
+#[rustfmt::skip]
+const SRSS_180: [XY; 44] = [
+  ( 1,  0), ( 2,  0), ( 1, -1), ( 2, -1), (-1,  0), (-2,  0), (-1, -1), (-2, -1), ( 0,  1), ( 3,  0), (-3,  0),
+  ( 0, -1), ( 0, -2), ( 1, -1), ( 1, -2), ( 0,  1), ( 0,  2), ( 1,  1), ( 1,  2), (-1,  0), ( 0, -3), ( 0,  3),
+  (-1,  0), (-2,  0), (-1,  1), (-2,  1), ( 1,  0), ( 2,  0), ( 1,  1), ( 2,  1), ( 0, -1), (-3,  0), ( 3,  0),
+  ( 0, -1), ( 0, -2), (-1, -1), (-1, -2), ( 0,  1), ( 0,  2), (-1,  1), (-1,  2), ( 1,  0), ( 0, -3), ( 0,  3),
+];
+
 fn srss_left(direction: Direction) -> &'static [XY] {
   match direction {
     Direction::Up => &SRSS_LEFT[0..4],
@@ -35,6 +44,15 @@ fn srss_right(direction: Direction) -> &'static [XY] {
   }
 }
 
+fn srss_180(direction: Direction) -> &'static [XY] {
+  match direction {
+    Direction::Up => &SRSS_180[0..11],
+    Direction::Left => &SRSS_180[11..22],
+    Direction::Down => &SRSS_180[22..33],
+    Direction::Right => &SRSS_180[33..44],
+  }
+}
+
 pub struct TetrominoI {}
 impl TetrominoI {
   #[rustfmt::skip]
@@ -61,6 +79,14 @@ impl TetrominoI {
     (-1, 0), ( 2, 0), (-1,  2), ( 2, -1),
   ];
 
+  #[rustfmt::skip]
+  const SRSS_180: [XY; 20] = [
+    (-1,  0), (-2,  0), ( 1, 0), ( 2, 0), ( 0, -1),
+    ( 0, -1), ( 0, -2), ( 0, 1), ( 0, 2), ( 1,  0),
+    ( 1,  0), ( 2,  0), (-1, 0), (-2, 0), ( 0,  1),
+    ( 0, -1), ( 0, -2), ( 0, 1), ( 0, 2), (-1,  0),
+  ];
+
   pub fn blocks(direction: Direction) -> &'static [XY] {
     match direction {
       Direction::Up => &TetrominoI::BLOCKS[0..4],
@@ -87,6 +113,15 @@ impl TetrominoI {
       Direction::Right => &TetrominoI::SRSS_RIGHT[12..16],
     }
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    match direction {
+      Direction::Up => &TetrominoI::SRSS_180[0..5],
+      Direction::Left => &TetrominoI::SRSS_180[5..10],
+      Direction::Down => &TetrominoI::SRSS_180[10..15],
+      Direction::Right => &TetrominoI::SRSS_180[15..20],
+    }
+  }
 }
 
 pub struct TetrominoJ {}
@@ -115,6 +150,10 @@ impl TetrominoJ {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
 
 pub struct TetrominoL {}
@@ -143,6 +182,10 @@ impl TetrominoL {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
 
 pub struct TetrominoO {}
@@ -171,6 +214,10 @@ impl TetrominoO {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
 
 pub struct TetrominoS {}
@@ -199,6 +246,10 @@ impl TetrominoS {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
 
 pub struct TetrominoT {}
@@ -227,6 +278,10 @@ impl TetrominoT {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
 
 pub struct TetrominoZ {}
@@ -255,4 +310,8 @@ impl TetrominoZ {
   pub fn srss_right(direction: Direction) -> &'static [XY] {
     srss_right(direction)
   }
+
+  pub fn srss_180(direction: Direction) -> &'static [XY] {
+    srss_180(direction)
+  }
 }
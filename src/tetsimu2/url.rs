@@ -0,0 +1,400 @@
+use crate::tetsimu2::core::FieldCellValue;
+use crate::tetsimu2::core::Tetromino;
+use crate::tetsimu2::core::MAX_FIELD_SIZE;
+use crate::tetsimu2::field::Field;
+use crate::tetsimu2::hold::Hold;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use std::convert::TryFrom;
+
+pub const DEFAULT_BASE_URL: &str = "https://cuboktahedron.github.io/tetsimu2/";
+pub const URL_VERSION: &str = "2.05";
+
+const ENCODE_TABLE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const MAX_RUN_LENGTH: usize = 7;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, FromPrimitive)]
+pub enum Tetsimu2Mode {
+  Simu = 0,
+  Edit = 1,
+  Replay = 2,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Tetsimu2UrlOptions {
+  pub next_num: u8,
+  pub ren: i8,
+  pub is_btb: bool,
+}
+
+impl Default for Tetsimu2UrlOptions {
+  fn default() -> Self {
+    Tetsimu2UrlOptions {
+      next_num: 5,
+      ren: -1,
+      is_btb: false,
+    }
+  }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Tetsimu2UrlContent {
+  pub mode: Tetsimu2Mode,
+  pub field: Field,
+  pub nexts: Vec<Tetromino>,
+  pub hold: Hold,
+  pub options: Tetsimu2UrlOptions,
+}
+
+impl Tetsimu2UrlContent {
+  pub fn new(field: Field) -> Tetsimu2UrlContent {
+    Tetsimu2UrlContent {
+      mode: Tetsimu2Mode::Simu,
+      field,
+      nexts: vec![],
+      hold: Hold::new(),
+      options: Tetsimu2UrlOptions::default(),
+    }
+  }
+}
+
+pub struct Tetsimu2UrlEncoder {
+  base_url: String,
+}
+
+impl Tetsimu2UrlEncoder {
+  pub fn new(base_url: &str) -> Tetsimu2UrlEncoder {
+    Tetsimu2UrlEncoder {
+      base_url: String::from(base_url),
+    }
+  }
+
+  pub fn encode(&self, content: &Tetsimu2UrlContent) -> String {
+    let params = vec![
+      ("v", String::from(URL_VERSION)),
+      ("m", (content.mode as u8).to_string()),
+      ("f", self.encode_field(&content.field)),
+      ("ns", encode_tetrominos(&content.nexts)),
+      (
+        "h",
+        encode_tetrominos(&content.hold.holded.into_iter().collect::<Vec<_>>()),
+      ),
+      ("ch", (content.hold.can_hold as u8).to_string()),
+      ("nn", content.options.next_num.to_string()),
+      ("r", content.options.ren.to_string()),
+      ("bb", (content.options.is_btb as u8).to_string()),
+    ];
+
+    let query = params
+      .iter()
+      .map(|(key, value)| format!("{}={}", key, value))
+      .collect::<Vec<_>>()
+      .join("&");
+    format!("{}?{}", self.base_url, query)
+  }
+
+  fn encode_field(&self, field: &Field) -> String {
+    let last = field
+      .data
+      .iter()
+      .rposition(|&cell| cell != FieldCellValue::None)
+      .map_or(0, |p| p + 1);
+
+    let mut encoded = String::new();
+    let mut p = 0;
+    while p < last {
+      let cell = field.data[p];
+      let mut run = 1;
+      while p + run < last && run < MAX_RUN_LENGTH && field.data[p + run] == cell {
+        run += 1;
+      }
+
+      let value = cell as usize * MAX_RUN_LENGTH + run - 1;
+      encoded.push(ENCODE_TABLE.chars().nth(value).unwrap());
+      p += run;
+    }
+
+    encoded
+  }
+}
+
+pub struct Tetsimu2UrlDecoder;
+
+impl Tetsimu2UrlDecoder {
+  pub fn new() -> Tetsimu2UrlDecoder {
+    Tetsimu2UrlDecoder {}
+  }
+
+  pub fn decode(&self, url: &str) -> Result<Tetsimu2UrlContent, String> {
+    let query = match url.find('?') {
+      Some(p) => &url[p + 1..],
+      None => url,
+    };
+    let query = match query.find('#') {
+      Some(p) => &query[..p],
+      None => query,
+    };
+
+    let mut content = Tetsimu2UrlContent::new(Field::new());
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+      let (key, value) = match param.find('=') {
+        Some(p) => (&param[..p], &param[p + 1..]),
+        None => (param, ""),
+      };
+      let value = &percent_decode(value)?;
+
+      match key {
+        "v" if value != URL_VERSION => {
+          return Err(format!("Unsupported url version passed. ({})", value))
+        }
+        "m" => {
+          content.mode = FromPrimitive::from_u8(parse_number(key, value)?)
+            .ok_or(format!("Invalid mode passed. ({})", value))?
+        }
+        "f" => content.field = self.decode_field(value)?,
+        "ns" => content.nexts = decode_tetrominos(value)?,
+        "h" => content.hold.holded = decode_tetrominos(value)?.into_iter().next(),
+        "ch" => content.hold.can_hold = parse_flag(key, value)?,
+        "nn" => content.options.next_num = parse_number(key, value)?,
+        "r" => content.options.ren = parse_number(key, value)?,
+        "bb" => content.options.is_btb = parse_flag(key, value)?,
+        _ => {}
+      }
+    }
+
+    Ok(content)
+  }
+
+  fn decode_field(&self, value: &str) -> Result<Field, String> {
+    let mut field = Field::new();
+    let mut p = 0;
+    for c in value.chars() {
+      let v = ENCODE_TABLE
+        .find(c)
+        .ok_or(format!("Invalid field character passed. ({})", c))?;
+      let cell: FieldCellValue = FromPrimitive::from_usize(v / MAX_RUN_LENGTH)
+        .ok_or(format!("Invalid field character passed. ({})", c))?;
+      let run = v % MAX_RUN_LENGTH + 1;
+
+      if p + run > MAX_FIELD_SIZE as usize {
+        return Err(String::from("Field data is too long."));
+      }
+
+      for cell_p in p..p + run {
+        field.data[cell_p] = cell;
+      }
+      p += run;
+    }
+
+    Ok(field)
+  }
+}
+
+fn encode_tetrominos(tetrominos: &[Tetromino]) -> String {
+  tetrominos
+    .iter()
+    .map(|&t| match t {
+      Tetromino::I => 'I',
+      Tetromino::J => 'J',
+      Tetromino::L => 'L',
+      Tetromino::O => 'O',
+      Tetromino::S => 'S',
+      Tetromino::T => 'T',
+      Tetromino::Z => 'Z',
+    })
+    .collect()
+}
+
+fn decode_tetrominos(value: &str) -> Result<Vec<Tetromino>, String> {
+  value.chars().map(Tetromino::try_from).collect()
+}
+
+// Browsers and chat tools may escape characters such as '-' in the copied url.
+fn percent_decode(value: &str) -> Result<String, String> {
+  let bytes = value.as_bytes();
+  let mut decoded = vec![];
+  let mut i = 0;
+  while i < bytes.len() {
+    if bytes[i] == b'%' {
+      let byte = value
+        .get(i + 1..i + 3)
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        .ok_or(format!("Invalid escape passed. ({})", value))?;
+      decoded.push(byte);
+      i += 3;
+    } else {
+      decoded.push(bytes[i]);
+      i += 1;
+    }
+  }
+
+  String::from_utf8(decoded).map_err(|_| format!("Invalid escape passed. ({})", value))
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+  value
+    .parse()
+    .map_err(|_| format!("Invalid parameter passed. ({}={})", key, value))
+}
+
+fn parse_flag(key: &str, value: &str) -> Result<bool, String> {
+  match value {
+    "0" => Ok(false),
+    "1" => Ok(true),
+    _ => Err(format!("Invalid parameter passed. ({}={})", key, value)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dev_utils::tests::tetsimu2::field::make_field;
+
+  #[test]
+  fn encode() {
+    #[rustfmt::skip]
+    let field = make_field(&format!("{}{}",
+      "NNNNNNNNNT",
+      "IIIIGGGGGG"));
+    let mut content = Tetsimu2UrlContent::new(field);
+    content.nexts = vec![Tetromino::J, Tetromino::L];
+    content.hold = Hold {
+      holded: Some(Tetromino::O),
+      can_hold: false,
+    };
+
+    let encoder = Tetsimu2UrlEncoder::new(DEFAULT_BASE_URL);
+    assert_eq!(
+      encoder.encode(&content),
+      "https://cuboktahedron.github.io/tetsimu2/?v=2.05&m=0&f=K9GBq&ns=JL&h=O&ch=0&nn=5&r=-1&bb=0"
+    );
+  }
+
+  #[test]
+  fn decode() {
+    let decoder = Tetsimu2UrlDecoder::new();
+    let content = decoder
+      .decode(
+        "https://cuboktahedron.github.io/tetsimu2/?v=2.05&m=1&f=K9GBq&ns=JL&h=O&ch=0&nn=3&r=2&bb=1",
+      )
+      .unwrap();
+
+    #[rustfmt::skip]
+    let field = make_field(&format!("{}{}",
+      "NNNNNNNNNT",
+      "IIIIGGGGGG"));
+    assert_eq!(
+      content,
+      Tetsimu2UrlContent {
+        mode: Tetsimu2Mode::Edit,
+        field,
+        nexts: vec![Tetromino::J, Tetromino::L],
+        hold: Hold {
+          holded: Some(Tetromino::O),
+          can_hold: false,
+        },
+        options: Tetsimu2UrlOptions {
+          next_num: 3,
+          ren: 2,
+          is_btb: true,
+        },
+      }
+    );
+  }
+
+  #[test]
+  fn decode_link_with_every_parameter() {
+    // Written by hand in the site's parameter layout, with an empty hold. Swap in a link copied
+    // from the site to check the field encoding against Tetsimu2 itself.
+    let decoder = Tetsimu2UrlDecoder::new();
+    let content = decoder
+      .decode(
+        "https://cuboktahedron.github.io/tetsimu2/?v=2.05&m=0&f=H6A8H4yAk6HyCkdHGd&ns=TSZLJOI&h=&ch=1&nn=5&r=-1&bb=0",
+      )
+      .unwrap();
+
+    #[rustfmt::skip]
+    let field = make_field(&[
+      "INNNNNNNOO",
+      "IZZNNNSSOO",
+      "IGZZNSSGGG",
+      "IGGGNGGGGG",
+    ].concat());
+    assert_eq!(content.mode, Tetsimu2Mode::Simu);
+    assert_eq!(content.field, field);
+    assert_eq!(
+      content.nexts,
+      vec![
+        Tetromino::T,
+        Tetromino::S,
+        Tetromino::Z,
+        Tetromino::L,
+        Tetromino::J,
+        Tetromino::O,
+        Tetromino::I,
+      ]
+    );
+    assert_eq!(content.hold, Hold::new());
+    assert_eq!(content.options, Tetsimu2UrlOptions::default());
+  }
+
+  #[test]
+  fn decode_should_use_defaults_for_missing_parameters() {
+    let decoder = Tetsimu2UrlDecoder::new();
+    assert_eq!(
+      decoder.decode("https://cuboktahedron.github.io/tetsimu2/"),
+      Ok(Tetsimu2UrlContent::new(Field::new()))
+    );
+  }
+
+  #[test]
+  fn decode_should_fail_for_invalid_parameters() {
+    let decoder = Tetsimu2UrlDecoder::new();
+    assert_eq!(
+      decoder.decode("?f=A*"),
+      Err(String::from("Invalid field character passed. (*)"))
+    );
+    assert_eq!(
+      decoder.decode("?ns=IX"),
+      Err(String::from("Cannot convert from 'X' to Tetromino"))
+    );
+    assert_eq!(
+      decoder.decode("?ch=2"),
+      Err(String::from("Invalid parameter passed. (ch=2)"))
+    );
+    assert_eq!(
+      decoder.decode("?v=2.04&f=K9GBq"),
+      Err(String::from("Unsupported url version passed. (2.04)"))
+    );
+    assert_eq!(
+      decoder.decode("?r=%2"),
+      Err(String::from("Invalid escape passed. (%2)"))
+    );
+  }
+
+  #[test]
+  fn decode_should_unescape_parameters() {
+    let decoder = Tetsimu2UrlDecoder::new();
+    let content = decoder
+      .decode("https://cuboktahedron.github.io/tetsimu2/?v=2%2E05&f=K9GBq&ns=J%4C&r=%2D1")
+      .unwrap();
+    assert_eq!(content.nexts, vec![Tetromino::J, Tetromino::L]);
+    assert_eq!(content.options.ren, -1);
+    assert_eq!(content.field.get_cell(0, 0), FieldCellValue::I);
+  }
+
+  #[test]
+  fn encode_and_decode_should_round_trip() {
+    let mut field = Field::new();
+    for p in 0..MAX_FIELD_SIZE {
+      let cell = FromPrimitive::from_i32((p * 7 / 3) % 9).unwrap();
+      field.set_cell(p % 10, p / 10, cell);
+    }
+
+    let mut content = Tetsimu2UrlContent::new(field);
+    content.nexts = vec![Tetromino::I, Tetromino::Z, Tetromino::T];
+
+    let url = Tetsimu2UrlEncoder::new(DEFAULT_BASE_URL).encode(&content);
+    assert_eq!(Tetsimu2UrlDecoder::new().decode(&url), Ok(content));
+  }
+}
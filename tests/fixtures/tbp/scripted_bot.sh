@@ -0,0 +1,16 @@
+#!/bin/sh
+# Minimal TBP bot that always suggests an O piece at the center.
+echo '{"type":"info","name":"scripted","version":"1.0","author":"","features":[]}'
+while read -r line; do
+  case "$line" in
+    *'"type":"rules"'*)
+      echo '{"type":"ready"}'
+      ;;
+    *'"type":"suggest"'*)
+      echo '{"type":"suggestion","moves":[{"location":{"type":"O","orientation":"north","x":4,"y":0},"spin":"none"}],"move_info":{"nodes":10,"nps":10,"depth":1,"extra":""}}'
+      ;;
+    *'"type":"quit"'*)
+      exit 0
+      ;;
+  esac
+done